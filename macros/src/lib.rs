@@ -0,0 +1,292 @@
+extern crate proc_macro;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    parse::Parse, parse::ParseStream, spanned::Spanned, Error, FnArg, GenericArgument, ImplItem, ItemImpl, LitStr,
+    PathArguments, Result, Signature, Type,
+};
+
+const METHODS: [&str; 7] = ["delete", "get", "head", "options", "patch", "post", "put"];
+
+/// Represents the metadata used to generate the routes of a controller.
+#[proc_macro_attribute]
+pub fn controller(metadata: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    proc_macro::TokenStream::from(_controller(TokenStream::from(metadata), TokenStream::from(input)))
+}
+
+fn _controller(metadata: TokenStream, input: TokenStream) -> TokenStream {
+    match syn::parse2::<ControllerAttribute>(metadata)
+        .and_then(|attribute| syn::parse2::<ItemImpl>(input).and_then(|item| generate(attribute, item)))
+    {
+        Ok(output) => output,
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+struct ControllerAttribute {
+    prefix: Option<LitStr>,
+}
+
+impl Parse for ControllerAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let prefix = if input.is_empty() { None } else { Some(input.parse()?) };
+        Ok(Self { prefix })
+    }
+}
+
+struct Route {
+    path: String,
+    span: Span,
+    methods: Vec<&'static str>,
+    actions: Vec<TokenStream>,
+}
+
+fn join(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+
+    if path.is_empty() || path == "/" {
+        if prefix.is_empty() {
+            "/".into()
+        } else {
+            prefix.into()
+        }
+    } else if path.starts_with('/') {
+        format!("{}{}", prefix, path)
+    } else {
+        format!("{}/{}", prefix, path)
+    }
+}
+
+fn is_ref_self(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return false;
+    };
+
+    segment.ident == "Ref"
+        && arguments.args.len() == 1
+        && matches!(
+            arguments.args.first(),
+            Some(GenericArgument::Type(Type::Path(ty))) if ty.path.is_ident("Self")
+        )
+}
+
+fn validate_receiver(signature: &Signature) -> Result<()> {
+    match signature.inputs.first() {
+        Some(FnArg::Receiver(receiver)) if receiver.colon_token.is_some() && is_ref_self(&receiver.ty) => Ok(()),
+        Some(FnArg::Receiver(receiver)) => Err(Error::new(
+            receiver.span(),
+            "A controller action must have the receiver `self: Ref<Self>`.",
+        )),
+        _ => Err(Error::new(
+            signature.span(),
+            "A controller action must have the receiver `self: Ref<Self>`.",
+        )),
+    }
+}
+
+fn generate(attribute: ControllerAttribute, mut item: ItemImpl) -> Result<TokenStream> {
+    if item.trait_.is_some() {
+        return Err(Error::new(
+            item.span(),
+            "#[controller] can only be applied to an inherent impl block.",
+        ));
+    }
+
+    let prefix_span = attribute.prefix.as_ref().map(LitStr::span);
+    let prefix = attribute.prefix.map(|p| p.value()).unwrap_or_default();
+    let mut routes = Vec::<Route>::new();
+
+    for member in item.items.iter_mut() {
+        let ImplItem::Fn(method) = member else {
+            continue;
+        };
+        let name = method.sig.ident.clone();
+        let mut attrs = Vec::with_capacity(method.attrs.len());
+
+        for attr in method.attrs.drain(..) {
+            let Some(http_method) = METHODS.iter().find(|m| attr.path().is_ident(m)) else {
+                attrs.push(attr);
+                continue;
+            };
+            validate_receiver(&method.sig)?;
+
+            let path: LitStr = attr.parse_args()?;
+            let full_path = join(&prefix, &path.value());
+
+            if !full_path.starts_with('/') {
+                return Err(Error::new(
+                    prefix_span.unwrap_or_else(|| path.span()),
+                    format!("The path '{}' must start with '/'.", full_path),
+                ));
+            }
+
+            let action = {
+                let http_method = format_ident!("{}", http_method);
+                quote! { .#http_method(Self::#name) }
+            };
+
+            if let Some(route) = routes.iter_mut().find(|r| r.path == full_path) {
+                if route.methods.contains(http_method) {
+                    return Err(Error::new(
+                        attr.span(),
+                        format!(
+                            "The method '{}' is already mapped to the path '{}'.",
+                            http_method.to_uppercase(),
+                            full_path
+                        ),
+                    ));
+                }
+
+                route.methods.push(http_method);
+                route.actions.push(action);
+            } else {
+                routes.push(Route {
+                    path: full_path,
+                    span: path.span(),
+                    methods: vec![http_method],
+                    actions: vec![action],
+                });
+            }
+        }
+
+        method.attrs = attrs;
+    }
+
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let self_ty = &item.self_ty;
+    let routes = routes.iter().map(|route| {
+        let path = LitStr::new(&route.path, route.span);
+        let actions = &route.actions;
+        quote! { .route_controller::<Self>(#path, |c| c #(#actions)*) }
+    });
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics ::di_axum::Controller for #self_ty #where_clause {
+            fn routes<S: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static>(
+            ) -> ::di_axum::__private::Router<S> {
+                use ::di_axum::prelude::RouterExt as _;
+                ::di_axum::__private::Router::new() #(#routes)*
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_should_combine_prefix_and_path() {
+        // arrange
+        let cases = [
+            ("", "/", "/"),
+            ("", "/{id}", "/{id}"),
+            ("/users", "/", "/users"),
+            ("/users/", "/{id}", "/users/{id}"),
+            ("/users", "{id}", "/users/{id}"),
+        ];
+
+        for (prefix, path, expected) in cases {
+            // act
+            let actual = join(prefix, path);
+
+            // assert
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn controller_should_reject_trait_impl() {
+        // arrange
+        let input = quote! { impl Clone for UserController {} };
+
+        // act
+        let output = _controller(TokenStream::new(), input).to_string();
+
+        // assert
+        assert!(output.contains("compile_error"));
+    }
+
+    #[test]
+    fn controller_should_reject_duplicate_method_and_path() {
+        // arrange
+        let input = quote! {
+            impl UserController {
+                #[get("/{id}")]
+                async fn get(self: Ref<Self>) {}
+
+                #[get("{id}")]
+                async fn find(self: Ref<Self>) {}
+            }
+        };
+
+        // act
+        let output = _controller(quote! { "/users" }, input).to_string();
+
+        // assert
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("The method 'GET' is already mapped to the path '/users/{id}'."));
+    }
+
+    #[test]
+    fn controller_should_reject_action_without_ref_self_receiver() {
+        // arrange
+        let input = quote! {
+            impl UserController {
+                #[get("/")]
+                async fn list(&self) {}
+            }
+        };
+
+        // act
+        let output = _controller(TokenStream::new(), input).to_string();
+
+        // assert
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("A controller action must have the receiver `self: Ref<Self>`."));
+    }
+
+    #[test]
+    fn controller_should_accept_action_with_ref_self_receiver() {
+        // arrange
+        let input = quote! {
+            impl UserController {
+                #[get("/")]
+                async fn list(self: di::Ref<Self>) {}
+            }
+        };
+
+        // act
+        let output = _controller(TokenStream::new(), input).to_string();
+
+        // assert
+        assert!(!output.contains("compile_error"));
+    }
+
+    #[test]
+    fn controller_should_reject_path_without_leading_slash() {
+        // arrange
+        let input = quote! {
+            impl UserController {
+                #[get("{id}")]
+                async fn find(self: Ref<Self>) {}
+            }
+        };
+
+        // act
+        let output = _controller(quote! { "users" }, input).to_string();
+
+        // assert
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("The path 'users/{id}' must start with '/'."));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::prelude::RouterExt;
+use axum::Router;
+use di::{ServiceCollection, ServiceProvider, ValidationError};
+
+/// Defines the behavior of a feature module that bundles services and routes.
+pub trait Module: Send + Sync {
+    /// Configures the services of the module.
+    ///
+    /// # Arguments
+    ///
+    /// * `services` - the [service collection](ServiceCollection) to add the services to
+    ///
+    /// # Remarks
+    ///
+    /// Services that can be replaced, such as for testing, should be registered with
+    /// [`try_add`](ServiceCollection::try_add).
+    fn configure_services(&self, services: &mut ServiceCollection);
+
+    /// Configures the routes of the module.
+    ///
+    /// # Arguments
+    ///
+    /// * `router` - the [router](Router) to add the routes to
+    fn configure_routes(&self, router: Router) -> Router {
+        router
+    }
+}
+
+/// Represents a builder for an application composed of [modules](Module).
+#[derive(Default)]
+pub struct AppBuilder {
+    services: ServiceCollection,
+    modules: Vec<Box<dyn Module>>,
+}
+
+impl AppBuilder {
+    /// Initializes a new application builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the specified module to the application.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - the [module](Module) to add
+    ///
+    /// # Remarks
+    ///
+    /// Modules are applied in the order they are added.
+    pub fn module(mut self, module: impl Module + 'static) -> Self {
+        self.modules.push(Box::new(module));
+        self
+    }
+
+    /// Configures the services of the application.
+    ///
+    /// # Arguments
+    ///
+    /// * `configure` - the function that adds services to the [service collection](ServiceCollection)
+    ///
+    /// # Remarks
+    ///
+    /// The services are added before the services of any module, which means a service registered by a module with
+    /// [`try_add`](ServiceCollection::try_add) can be replaced, such as for testing.
+    pub fn services<F: FnOnce(&mut ServiceCollection)>(mut self, configure: F) -> Self {
+        configure(&mut self.services);
+        self
+    }
+
+    /// Builds the service provider and router of the application.
+    ///
+    /// # Remarks
+    ///
+    /// The service collection the service provider was built from is also returned.
+    pub(crate) fn build_parts(self) -> Result<(ServiceCollection, ServiceProvider, Router), ValidationError> {
+        let Self { mut services, modules } = self;
+
+        for module in &modules {
+            module.configure_services(&mut services);
+        }
+
+        #[cfg(debug_assertions)]
+        crate::prelude::ServiceCollectionExt::add_lock_tracking(&mut services);
+
+        let provider = services.build_provider()?;
+        let router = modules
+            .iter()
+            .fold(Router::new(), |router, module| module.configure_routes(router));
+        let router = if router.has_routes() {
+            router.with_provider(provider.clone())
+        } else {
+            router
+        };
+
+        Ok((services, provider, router))
+    }
+
+    /// Builds the router of the application.
+    ///
+    /// # Remarks
+    ///
+    /// The services of each module are configured in order, followed by the routes of each module in order. The
+    /// service provider is added to the router last.
+    pub fn build(self) -> Result<Router, ValidationError> {
+        self.build_parts().map(|(_, _, router)| router)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::Inject;
+    use axum::{routing::get, test_helpers::TestClient};
+    use di::{injectable, Injectable};
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> &str;
+    }
+
+    #[injectable(Greeter)]
+    struct English;
+
+    impl Greeter for English {
+        fn greet(&self) -> &str {
+            "Hello"
+        }
+    }
+
+    #[injectable(Greeter)]
+    struct Spanish;
+
+    impl Greeter for Spanish {
+        fn greet(&self) -> &str {
+            "Hola"
+        }
+    }
+
+    struct Greetings;
+
+    impl Module for Greetings {
+        fn configure_services(&self, services: &mut ServiceCollection) {
+            services.try_add(English::scoped());
+        }
+
+        fn configure_routes(&self, router: Router) -> Router {
+            router.route(
+                "/greet",
+                get(|Inject(greeter): Inject<dyn Greeter>| async move { greeter.greet().to_owned() }),
+            )
+        }
+    }
+
+    struct Health;
+
+    impl Module for Health {
+        fn configure_services(&self, _services: &mut ServiceCollection) {}
+
+        fn configure_routes(&self, router: Router) -> Router {
+            router.route("/health", get(|| async { "Healthy" }))
+        }
+    }
+
+    #[tokio::test]
+    async fn build_should_apply_modules() {
+        // arrange
+        let app = AppBuilder::new().module(Greetings).module(Health).build().unwrap();
+        let client = TestClient::new(app);
+
+        // act
+        let greeting = client.get("/greet").into_future().await.text().await;
+        let health = client.get("/health").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&greeting, "Hello");
+        assert_eq!(&health, "Healthy");
+    }
+
+    #[tokio::test]
+    async fn services_should_replace_module_services() {
+        // arrange
+        let app = AppBuilder::new()
+            .services(|services| {
+                services.add(Spanish::scoped());
+            })
+            .module(Greetings)
+            .build()
+            .unwrap();
+        let client = TestClient::new(app);
+
+        // act
+        let greeting = client.get("/greet").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&greeting, "Hola");
+    }
+}
@@ -0,0 +1,256 @@
+use crate::middleware::BoxFuture;
+use di::{Ref, ServiceCollection, ServiceProvider, Type};
+use std::time::Duration;
+use tokio::task::{JoinError, JoinSet};
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
+/// Defines the behavior of a long-running service that runs in the background of a [web host](crate::WebHost).
+///
+/// # Remarks
+///
+/// Background services are registered with [`try_add_to_all`](di::ServiceCollection::try_add_to_all). Each background
+/// service is resolved from its own scope, which lives until the service completes, and can depend on the same
+/// singleton services as the request handlers.
+pub trait BackgroundService: Send + Sync {
+    /// Runs the background service.
+    ///
+    /// # Arguments
+    ///
+    /// * `shutdown` - the [token](CancellationToken) that is cancelled when the host is shutting down
+    fn run(&self, shutdown: CancellationToken) -> BoxFuture<'_, ()>;
+}
+
+/// Starts all registered background services.
+///
+/// # Arguments
+///
+/// * `services` - the [service collection](ServiceCollection) the root service provider was built from
+/// * `provider` - the root [service provider](ServiceProvider)
+/// * `shutdown` - the [token](CancellationToken) that is cancelled when the host is shutting down
+///
+/// # Remarks
+///
+/// Each descriptor of a background service is resolved exactly once from a new scope.
+pub(crate) fn start(
+    services: &ServiceCollection,
+    provider: &ServiceProvider,
+    shutdown: &CancellationToken,
+) -> JoinSet<()> {
+    let mut tasks = JoinSet::new();
+    let key = Type::of::<dyn BackgroundService>();
+
+    for descriptor in services.iter().filter(|descriptor| *descriptor.service_type() == key) {
+        let scope = provider.create_scope();
+        let service = descriptor.get(&scope);
+        let Some(service) = service.downcast_ref::<Ref<dyn BackgroundService>>().cloned() else {
+            continue;
+        };
+        let shutdown = shutdown.child_token();
+
+        tasks.spawn(async move {
+            // the scope lives until the background service completes
+            let _scope = scope;
+            service.run(shutdown).await;
+        });
+    }
+
+    tasks
+}
+
+/// Waits for the background services to complete.
+///
+/// # Arguments
+///
+/// * `tasks` - the running background services
+/// * `limit` - the maximum [duration](Duration) to wait before the remaining background services are aborted
+///
+/// # Remarks
+///
+/// All of the background services are waited for, after which the [error](JoinError) of the first background service
+/// that panicked, if any, is returned. A background service that is aborted is not an error.
+pub(crate) async fn stop(mut tasks: JoinSet<()>, limit: Duration) -> Result<(), JoinError> {
+    let mut panicked = None;
+    let mut join = |result: Result<(), JoinError>| {
+        if let Err(error) = result {
+            if error.is_panic() && panicked.is_none() {
+                panicked = Some(error);
+            }
+        }
+    };
+
+    if timeout(limit, async {
+        while let Some(result) = tasks.join_next().await {
+            join(result);
+        }
+    })
+    .await
+    .is_err()
+    {
+        tasks.abort_all();
+
+        while let Some(result) = tasks.join_next().await {
+            join(result);
+        }
+    }
+
+    panicked.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HostError, WebHost};
+    use di::{injectable, scoped_factory, singleton_factory, Injectable};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::net::TcpListener;
+    use tokio::sync::{oneshot, Notify};
+
+    #[derive(Default)]
+    struct Activity {
+        started: AtomicUsize,
+        stopped: AtomicUsize,
+        running: Notify,
+    }
+
+    #[injectable(BackgroundService)]
+    struct Worker {
+        activity: Ref<Activity>,
+    }
+
+    impl BackgroundService for Worker {
+        fn run(&self, shutdown: CancellationToken) -> BoxFuture<'_, ()> {
+            Box::pin(async move {
+                self.activity.started.fetch_add(1, Ordering::SeqCst);
+                self.activity.running.notify_one();
+                shutdown.cancelled().await;
+                self.activity.stopped.fetch_add(1, Ordering::SeqCst);
+            })
+        }
+    }
+
+    #[injectable(BackgroundService)]
+    struct Stubborn;
+
+    impl BackgroundService for Stubborn {
+        fn run(&self, _shutdown: CancellationToken) -> BoxFuture<'_, ()> {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    #[injectable(BackgroundService)]
+    struct Faulty;
+
+    impl BackgroundService for Faulty {
+        fn run(&self, _shutdown: CancellationToken) -> BoxFuture<'_, ()> {
+            Box::pin(async { panic!("The background service failed.") })
+        }
+    }
+
+    struct Connection;
+
+    #[injectable(BackgroundService)]
+    struct Consumer {
+        _connection: Ref<Connection>,
+    }
+
+    impl BackgroundService for Consumer {
+        fn run(&self, _shutdown: CancellationToken) -> BoxFuture<'_, ()> {
+            Box::pin(async {})
+        }
+    }
+
+    async fn listener() -> TcpListener {
+        TcpListener::bind("127.0.0.1:0").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn host_should_run_background_services_until_shutdown() {
+        // arrange
+        let activity = Ref::new(Activity::default());
+        let (stop, stopped) = oneshot::channel::<()>();
+        let shared = activity.clone();
+        let host = WebHost::default()
+            .services(|services| {
+                services
+                    .add(singleton_factory(move |_| shared.clone()))
+                    .try_add_to_all(Worker::transient());
+            })
+            .listener(listener().await)
+            .with_shutdown_signal(async move {
+                stopped.await.ok();
+            });
+        let running = tokio::spawn(host.run());
+
+        // act
+        timeout(Duration::from_secs(5), activity.running.notified())
+            .await
+            .unwrap();
+        stop.send(()).unwrap();
+        running.await.unwrap().unwrap();
+
+        // assert
+        assert_eq!(activity.started.load(Ordering::SeqCst), 1);
+        assert_eq!(activity.stopped.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn host_should_abort_background_services_after_shutdown_timeout() {
+        // arrange
+        let host = WebHost::default()
+            .services(|services| {
+                services.try_add_to_all(Stubborn::transient());
+            })
+            .listener(listener().await)
+            .with_shutdown_signal(async {})
+            .with_shutdown_timeout(Duration::from_millis(10));
+
+        // act
+        let result = timeout(Duration::from_secs(5), host.run()).await;
+
+        // assert
+        assert!(matches!(result, Ok(Ok(()))));
+    }
+
+    #[tokio::test]
+    async fn host_should_resolve_each_background_service_from_its_own_scope() {
+        // arrange
+        let opened = Ref::new(AtomicUsize::new(0));
+        let counter = opened.clone();
+        let host = WebHost::default()
+            .services(|services| {
+                services
+                    .add(scoped_factory(move |_| {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Ref::new(Connection)
+                    }))
+                    .add(Consumer::transient())
+                    .add(Consumer::transient());
+            })
+            .listener(listener().await)
+            .with_shutdown_signal(async {});
+
+        // act
+        host.run().await.unwrap();
+
+        // assert
+        assert_eq!(opened.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn host_should_fail_when_background_service_panics() {
+        // arrange
+        let host = WebHost::default()
+            .services(|services| {
+                services.try_add_to_all(Faulty::transient());
+            })
+            .listener(listener().await)
+            .with_shutdown_signal(async {});
+
+        // act
+        let result = host.run().await;
+
+        // assert
+        assert!(matches!(result, Err(HostError::Background(error)) if error.is_panic()));
+    }
+}
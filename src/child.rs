@@ -0,0 +1,353 @@
+use crate::scope::{self, Ancestors, Origin};
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use di::{
+    scoped_factory, transient, Ref, ServiceCollection, ServiceDescriptor, ServiceProvider, Type, ValidationError,
+};
+use std::any::{type_name, Any};
+use std::sync::OnceLock;
+
+/// Represents the service provider of the parent scope for child services.
+///
+/// # Remarks
+///
+/// A child service is resolved from the child service provider, which cannot resolve the services of its parent. A
+/// child service that depends on a service of its parent can resolve it from the parent service provider. The parent
+/// service provider is always available to child services. It is also available to the services of a provider added
+/// with [`Stacking::Child`](crate::Stacking::Child) when the provider registers it as a scoped service.
+#[derive(Default)]
+pub struct ParentProvider(OnceLock<ServiceProvider>);
+
+impl ParentProvider {
+    /// Gets the service provider of the parent scope, if any.
+    pub fn provider(&self) -> Option<&ServiceProvider> {
+        self.0.get()
+    }
+}
+
+/// Represents the implementation type of a service that is resolved from the parent service provider.
+struct FromParent;
+
+/// Creates a descriptor that resolves a service from the parent service provider.
+///
+/// # Remarks
+///
+/// The service is resolved from the parent scope of each request, which means it has the same lifetime as if it were
+/// resolved by the parent.
+pub(crate) fn from_parent<T: ?Sized + Any + Send + Sync>() -> ServiceDescriptor {
+    transient::<T, FromParent>().from(|services| {
+        let parent = services.get_required::<ParentProvider>();
+
+        match parent.provider() {
+            Some(provider) => provider.get_required::<T>(),
+            None => panic!(
+                "No parent service provider is available to resolve '{}'.",
+                type_name::<T>()
+            ),
+        }
+    })
+}
+
+/// Represents the service provider for child services.
+#[derive(Clone)]
+pub(crate) struct Child {
+    provider: ServiceProvider,
+    parent: Option<Ref<str>>,
+}
+
+/// Builds the service provider for child services.
+///
+/// # Arguments
+///
+/// * `configure` - the function that adds the child services
+///
+/// # Remarks
+///
+/// The child services are not valid when a dependency on a parent service has not been added with
+/// [`add_parent_service`](crate::prelude::ServiceCollectionExt::add_parent_service).
+pub(crate) fn build<F: FnOnce(&mut ServiceCollection)>(configure: F) -> Result<Child, ValidationError> {
+    let mut services = ServiceCollection::new();
+
+    configure(&mut services);
+    services.try_add(scoped_factory(|_| Ref::new(ParentProvider::default())));
+
+    // a parent service can only be resolved when the child services are layered over a parent service provider
+    let parent = services
+        .iter()
+        .find(|descriptor| *descriptor.implementation_type() == Type::of::<FromParent>())
+        .map(|descriptor| Ref::from(descriptor.service_type().name()));
+
+    Ok(Child {
+        provider: services.build_provider()?,
+        parent,
+    })
+}
+
+/// Enters a new child scope for a request.
+///
+/// # Arguments
+///
+/// * `request` - the [request](Request) the scope is added to
+/// * `provider` - the [service provider](ServiceProvider) the child scope is created from
+///
+/// # Remarks
+///
+/// The existing scope of the request, if any, becomes the parent of the new scope.
+pub(crate) fn enter(request: &mut Request, provider: &ServiceProvider) {
+    if request.extensions().get::<ServiceProvider>().is_none() {
+        scope::begin(request, provider);
+        return;
+    }
+
+    let scope = scope::create(request, provider);
+    let extensions = request.extensions_mut();
+
+    if let Some(parent) = extensions.remove::<ServiceProvider>() {
+        let mut ancestors = extensions.remove::<Ancestors>().unwrap_or_default();
+
+        extensions.remove::<Origin>();
+
+        if let Some(slot) = scope.get::<ParentProvider>() {
+            let _ = slot.0.set(parent.clone());
+        }
+
+        ancestors.0.insert(0, parent);
+        extensions.insert(scope);
+        extensions.insert(ancestors);
+    }
+}
+
+pub(crate) async fn child_middleware(State(child): State<Child>, mut request: Request, next: Next) -> Response {
+    if let Some(parent) = &child.parent {
+        if request.extensions().get::<ServiceProvider>().is_none() {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("No parent service provider is available to resolve '{}'.", parent),
+            )
+                .into_response();
+        }
+    }
+
+    enter(&mut request, &child.provider);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::{prelude::*, Inject, InjectAll};
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, transient_factory, Injectable};
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    #[injectable(Greeter)]
+    struct DefaultGreeter;
+
+    #[injectable(Greeter)]
+    struct AdminGreeter;
+
+    impl Greeter for DefaultGreeter {
+        fn greet(&self) -> String {
+            "Hello".into()
+        }
+    }
+
+    impl Greeter for AdminGreeter {
+        fn greet(&self) -> String {
+            "Hello, admin".into()
+        }
+    }
+
+    #[derive(Default)]
+    struct Settings {
+        name: String,
+    }
+
+    #[injectable]
+    struct Banner {
+        settings: Ref<Settings>,
+    }
+
+    async fn greet(Inject(greeter): Inject<dyn Greeter>) -> String {
+        greeter.greet()
+    }
+
+    fn new_provider() -> ServiceProvider {
+        ServiceCollection::new()
+            .add(DefaultGreeter::singleton())
+            .add(transient_factory(|_| Ref::new(Settings { name: "Parent".into() })))
+            .build_provider()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn child_services_should_override_parent_services() {
+        // arrange
+        let admin = Router::new()
+            .route("/greet", get(greet))
+            .with_child_services(|services| {
+                services.add(AdminGreeter::scoped());
+            });
+        let app = Router::new()
+            .route("/greet", get(greet))
+            .nest("/admin", admin)
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let parent = client.get("/greet").into_future().await.text().await;
+        let child = client.get("/admin/greet").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&parent, "Hello");
+        assert_eq!(&child, "Hello, admin");
+    }
+
+    #[tokio::test]
+    async fn child_services_should_fall_back_to_parent_services() {
+        // arrange
+        async fn handler(Inject(settings): Inject<Settings>, InjectAll(greeters): InjectAll<dyn Greeter>) -> String {
+            let greetings: Vec<_> = greeters.iter().map(|greeter| greeter.greet()).collect();
+            format!("{}: {}", settings.name, greetings.join(","))
+        }
+
+        let admin = Router::new()
+            .route("/test", get(handler))
+            .with_child_services(|services| {
+                services.add(AdminGreeter::scoped());
+            });
+        let app = Router::new().nest("/admin", admin).with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/admin/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Parent: Hello,Hello, admin");
+    }
+
+    #[tokio::test]
+    async fn child_service_should_resolve_dependency_from_parent_provider() {
+        // arrange
+        struct Banner(String);
+
+        async fn handler(Inject(banner): Inject<Banner>) -> String {
+            banner.0.clone()
+        }
+
+        let admin = Router::new()
+            .route("/test", get(handler))
+            .with_child_services(|services| {
+                services.add(scoped_factory(|sp| {
+                    let parent = sp.get_required::<ParentProvider>();
+                    let settings = parent.provider().unwrap().get_required::<Settings>();
+                    Ref::new(Banner(format!("{} (admin)", settings.name)))
+                }));
+            });
+        let app = Router::new().nest("/admin", admin).with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/admin/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Parent (admin)");
+    }
+
+    #[tokio::test]
+    async fn child_service_should_depend_on_parent_service() {
+        // arrange
+        async fn handler(Inject(banner): Inject<Banner>) -> String {
+            format!("{} (admin)", banner.settings.name)
+        }
+
+        let admin = Router::new()
+            .route("/test", get(handler))
+            .with_child_services(|services| {
+                services.add(Banner::scoped()).add_parent_service::<Settings>();
+            });
+        let app = Router::new().nest("/admin", admin).with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/admin/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Parent (admin)");
+    }
+
+    #[tokio::test]
+    async fn request_should_fail_with_500_for_parent_service_without_parent_provider() {
+        // arrange
+        async fn handler(Inject(banner): Inject<Banner>) -> String {
+            banner.settings.name.clone()
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_child_services(|services| {
+                services.add(Banner::scoped()).add_parent_service::<Settings>();
+            });
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response
+            .text()
+            .await
+            .contains("No parent service provider is available to resolve"));
+    }
+
+    #[test]
+    fn try_with_child_services_should_fail_for_missing_parent_service() {
+        // arrange
+        let router = Router::<()>::new().route("/test", get(greet));
+
+        // act
+        let result = router.try_with_child_services(|services| {
+            services.add(Banner::scoped());
+        });
+
+        // assert
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn child_services_should_be_used_without_parent_provider() {
+        // arrange
+        let app = Router::new()
+            .route("/greet", get(greet))
+            .with_child_services(|services| {
+                services.add(AdminGreeter::scoped());
+            });
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/greet").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Hello, admin");
+    }
+}
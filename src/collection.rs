@@ -0,0 +1,183 @@
+use crate::{
+    child, context,
+    inject_keyed_map::{keyed_entry, KeyMismatchError},
+    inject_lock::LockGraph,
+    scope::initializer,
+    scoped_value::{self, bridge, Bridged},
+    warm_up::{self, WarmUpError},
+    KeyName, RequestContext, WarmUp,
+};
+use di::{singleton_factory, Ref, ServiceCollection, ServiceDescriptor, ServiceProvider, Type};
+use std::any::{type_name, Any};
+
+/// Provides [service collection][ServiceCollection] extension methods.
+pub trait ServiceCollectionExt {
+    /// Adds a keyed service, which is mapped by the type name of its key.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor` - the [descriptor](ServiceDescriptor) of the keyed service
+    ///
+    /// # Remarks
+    ///
+    /// Keyed services added by this method can be injected together with
+    /// [`InjectKeyedMap`](crate::InjectKeyedMap). The service is not added and an error is returned when the descriptor
+    /// is not for `TSvc` keyed by `TKey`.
+    fn add_keyed<TKey, TSvc>(
+        &mut self,
+        descriptor: impl Into<ServiceDescriptor>,
+    ) -> Result<&mut Self, KeyMismatchError>
+    where
+        TKey: 'static,
+        TSvc: ?Sized + Any + Send + Sync;
+
+    /// Adds a keyed service, which is mapped by the [name](KeyName) of its key.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor` - the [descriptor](ServiceDescriptor) of the keyed service
+    ///
+    /// # Remarks
+    ///
+    /// Keyed services added by this method can be injected together with
+    /// [`InjectKeyedMap`](crate::InjectKeyedMap). The service is not added and an error is returned when the descriptor
+    /// is not for `TSvc` keyed by `TKey`.
+    fn add_named_keyed<TKey, TSvc>(
+        &mut self,
+        descriptor: impl Into<ServiceDescriptor>,
+    ) -> Result<&mut Self, KeyMismatchError>
+    where
+        TKey: KeyName + 'static,
+        TSvc: ?Sized + Any + Send + Sync;
+
+    /// Adds the dependencies of the registered mutable services to lock tracking.
+    ///
+    /// # Remarks
+    ///
+    /// In debug builds, the mutable services handed out to a handler are tracked for each request. A service provider
+    /// does not retain the dependencies of its services, so two mutable services that depend on the same scoped or
+    /// singleton mutable service are only reported as a [deadlock](crate::LockError::Deadlock) when their dependencies
+    /// have been captured by this method. The dependencies are captured when this method is called, which means it
+    /// should be called after all of the mutable services have been registered.
+    fn add_lock_tracking(&mut self) -> &mut Self;
+
+    /// Adds the [request context](RequestContext) as a scoped service.
+    ///
+    /// # Remarks
+    ///
+    /// The request context is set when the scope for a request is created. Services that depend on the request context
+    /// must be scoped or transient.
+    fn add_request_context(&mut self) -> &mut Self;
+
+    /// Adds a service that is resolved from the parent service provider.
+    ///
+    /// # Remarks
+    ///
+    /// Child services are built into their own service provider, which cannot resolve the services of its parent. A
+    /// child service that depends on a parent service is only valid when the parent service is added to the
+    /// [child services](crate::prelude::RouterExt::with_child_services) by this method. The service is resolved from
+    /// the parent scope of each request and is not added if it has already been registered.
+    fn add_parent_service<T: ?Sized + Any + Send + Sync>(&mut self) -> &mut Self;
+
+    /// Adds a [scoped value](crate::ScopedValue) that is set after the scope for a request has been created.
+    ///
+    /// # Remarks
+    ///
+    /// The scoped value is typically set by a middleware using
+    /// [`RequestExt::services_mut`](crate::prelude::RequestExt::services_mut). Services that depend on the scoped value
+    /// must be scoped or transient.
+    fn add_scoped_value<T: Any + Send + Sync>(&mut self) -> &mut Self;
+
+    /// Adds a request extension as a [scoped value](crate::ScopedValue).
+    ///
+    /// # Remarks
+    ///
+    /// The request extension is cloned into the scope when the scope for a request is created. Only request extensions
+    /// that have been added before the scope is created, such as by a middleware layered over the service provider,
+    /// are available. A service that depends on `Ref<ScopedValue<T>>` can determine whether the request extension was
+    /// present.
+    fn bridge_extension<T: Clone + Any + Send + Sync>(&mut self) -> &mut Self;
+
+    /// Resolves the registered services from a service provider built from the service collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - the [service provider](ServiceProvider) built from the service collection
+    /// * `warm_up` - the [services](WarmUp) to resolve
+    ///
+    /// # Remarks
+    ///
+    /// Resolution failures otherwise surface when a route is first requested. Warming up the service provider before
+    /// it is added to a router resolves each service, catches a service that panics, such as a factory that cannot
+    /// read its configuration, and reports all of the failures together so that an application can fail at startup.
+    /// Singleton services that are resolved remain resolved by the service provider. A panic can only be caught when
+    /// the profile uses `panic = "unwind"`, which is the default.
+    fn warm_up(&self, provider: &ServiceProvider, warm_up: WarmUp) -> Result<(), WarmUpError>;
+}
+
+fn add_keyed_as<TKey, TSvc>(
+    services: &mut ServiceCollection,
+    name: &'static str,
+    descriptor: ServiceDescriptor,
+) -> Result<(), KeyMismatchError>
+where
+    TKey: 'static,
+    TSvc: ?Sized + Any + Send + Sync,
+{
+    if *descriptor.service_type() == Type::keyed::<TKey, TSvc>() {
+        services.add(descriptor).try_add_to_all(keyed_entry::<TKey, TSvc>(name));
+        Ok(())
+    } else {
+        Err(KeyMismatchError::new(type_name::<TSvc>(), type_name::<TKey>()))
+    }
+}
+
+impl ServiceCollectionExt for ServiceCollection {
+    fn add_keyed<TKey, TSvc>(&mut self, descriptor: impl Into<ServiceDescriptor>) -> Result<&mut Self, KeyMismatchError>
+    where
+        TKey: 'static,
+        TSvc: ?Sized + Any + Send + Sync,
+    {
+        add_keyed_as::<TKey, TSvc>(self, type_name::<TKey>(), descriptor.into())?;
+        Ok(self)
+    }
+
+    fn add_named_keyed<TKey, TSvc>(
+        &mut self,
+        descriptor: impl Into<ServiceDescriptor>,
+    ) -> Result<&mut Self, KeyMismatchError>
+    where
+        TKey: KeyName + 'static,
+        TSvc: ?Sized + Any + Send + Sync,
+    {
+        add_keyed_as::<TKey, TSvc>(self, TKey::NAME, descriptor.into())?;
+        Ok(self)
+    }
+
+    fn add_lock_tracking(&mut self) -> &mut Self {
+        let graph = Ref::new(LockGraph::new(self));
+        self.add(singleton_factory(move |_| graph.clone()))
+    }
+
+    fn add_request_context(&mut self) -> &mut Self {
+        self.try_add_all(scoped_value::descriptors::<RequestContext>())
+            .try_add_to_all(initializer::<RequestContext>(context::initialize))
+    }
+
+    fn add_parent_service<T: ?Sized + Any + Send + Sync>(&mut self) -> &mut Self {
+        self.try_add(child::from_parent::<T>())
+    }
+
+    fn add_scoped_value<T: Any + Send + Sync>(&mut self) -> &mut Self {
+        self.try_add_all(scoped_value::descriptors::<T>())
+    }
+
+    fn bridge_extension<T: Clone + Any + Send + Sync>(&mut self) -> &mut Self {
+        self.add_scoped_value::<T>()
+            .try_add_to_all(initializer::<Bridged<T>>(bridge::<T>))
+    }
+
+    fn warm_up(&self, provider: &ServiceProvider, warm_up: WarmUp) -> Result<(), WarmUpError> {
+        warm_up::warm_up(self, provider, warm_up)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::ScopedValue;
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{Extensions, HeaderMap, Method, Uri, Version},
+};
+use di::{Ref, ServiceProvider};
+
+/// Represents the metadata of the current request.
+///
+/// # Remarks
+///
+/// The request context is a scoped service that is only available when it has been added with
+/// [`add_request_context`](crate::prelude::ServiceCollectionExt::add_request_context). The extensions are a snapshot
+/// of the request extensions when the scope was created.
+#[derive(Clone, Debug)]
+pub struct RequestContext {
+    method: Method,
+    uri: Uri,
+    version: Version,
+    headers: HeaderMap,
+    extensions: Extensions,
+    matched_path: Option<String>,
+}
+
+impl RequestContext {
+    /// Initializes a new request context.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the [request](Request) to create the context for
+    pub fn new(request: &Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            uri: request.uri().clone(),
+            version: request.version(),
+            headers: request.headers().clone(),
+            extensions: request.extensions().clone(),
+            matched_path: request
+                .extensions()
+                .get::<MatchedPath>()
+                .map(|path| path.as_str().to_owned()),
+        }
+    }
+
+    /// Gets the HTTP method of the request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Gets the URI of the request.
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// Gets the HTTP version of the request.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Gets the headers of the request.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Gets a snapshot of the request extensions.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Gets the path of the route that matched the request, if any.
+    pub fn matched_path(&self) -> Option<&str> {
+        self.matched_path.as_deref()
+    }
+}
+
+/// Initializes the request context of a new scope.
+///
+/// # Arguments
+///
+/// * `scope` - the new scope
+/// * `request` - the [request](Request) the scope was created for
+pub(crate) fn initialize(scope: &ServiceProvider, request: &Request) {
+    if let Some(slot) = scope.get::<ScopedValue<RequestContext>>() {
+        let _ = slot.set(Ref::new(RequestContext::new(request)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::{prelude::*, Inject};
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, Injectable, ServiceCollection};
+
+    #[injectable]
+    struct Tracer {
+        context: Ref<RequestContext>,
+    }
+
+    impl Tracer {
+        fn trace(&self) -> String {
+            format!(
+                "{} {} {} {}",
+                self.context.method(),
+                self.context.uri().path(),
+                self.context.matched_path().unwrap_or_default(),
+                self.context
+                    .headers()
+                    .get("x-trace-id")
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default(),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn request_context_should_be_injected_into_scoped_service() {
+        // arrange
+        async fn handler(Inject(tracer): Inject<Tracer>) -> String {
+            tracer.trace()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(Tracer::scoped())
+            .add_request_context()
+            .build_provider()
+            .unwrap();
+        let app = Router::new().route("/users/{id}", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/users/42").header("x-trace-id", "abc").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "GET /users/42 /users/{id} abc");
+    }
+
+    #[tokio::test]
+    async fn request_context_should_be_unique_to_each_request() {
+        // arrange
+        async fn handler(Inject(context): Inject<RequestContext>) -> String {
+            context.uri().path().into()
+        }
+
+        let provider = ServiceCollection::new().add_request_context().build_provider().unwrap();
+        let app = Router::new()
+            .route("/one", get(handler))
+            .route("/two", get(handler))
+            .with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let one = client.get("/one").into_future().await.text().await;
+        let two = client.get("/two").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&one, "/one");
+        assert_eq!(&two, "/two");
+    }
+}
@@ -0,0 +1,107 @@
+use axum::Router;
+use di::ServiceProvider;
+
+/// Defines the behavior of a service that contributes routes to an application.
+///
+/// # Remarks
+///
+/// Route contributors are typically registered with
+/// [`try_add_to_all`](di::ServiceCollection::try_add_to_all) so that each feature of an application can add its
+/// routes by registering a single service.
+pub trait RouteContributor: Send + Sync {
+    /// Gets the routes contributed to the application.
+    fn routes(&self) -> Router;
+}
+
+/// Merges the routes of all registered route contributors.
+///
+/// # Arguments
+///
+/// * `provider` - the [service provider](ServiceProvider) used to resolve the route contributors
+pub(crate) fn merge<S: Clone + Send + Sync + 'static>(provider: &ServiceProvider) -> Router<S> {
+    provider
+        .get_all::<dyn RouteContributor>()
+        .fold(Router::new(), |router, contributor| router.merge(contributor.routes()))
+        .with_state(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::prelude::*;
+    use crate::Inject;
+    use axum::{http::StatusCode, routing::get, test_helpers::TestClient};
+    use di::{injectable, Injectable, ServiceCollection};
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    #[injectable(Greeter)]
+    struct English;
+
+    impl Greeter for English {
+        fn greet(&self) -> String {
+            "Hello".into()
+        }
+    }
+
+    #[injectable(RouteContributor)]
+    struct Users;
+
+    impl RouteContributor for Users {
+        fn routes(&self) -> Router {
+            Router::new().route(
+                "/users",
+                get(|Inject(greeter): Inject<dyn Greeter>| async move { greeter.greet() }),
+            )
+        }
+    }
+
+    #[injectable(RouteContributor)]
+    struct Orders;
+
+    impl RouteContributor for Orders {
+        fn routes(&self) -> Router {
+            Router::new().route("/orders", get(|| async { "Orders" }))
+        }
+    }
+
+    #[tokio::test]
+    async fn from_services_should_merge_contributed_routes() {
+        // arrange
+        let provider = ServiceCollection::new()
+            .add(English::scoped())
+            .try_add_to_all(Users::singleton())
+            .try_add_to_all(Orders::singleton())
+            .build_provider()
+            .unwrap();
+        let app = Router::from_services(&provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let users = client.get("/users").into_future().await.text().await;
+        let orders = client.get("/orders").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&users, "Hello");
+        assert_eq!(&orders, "Orders");
+    }
+
+    #[tokio::test]
+    async fn from_services_should_have_no_routes_without_contributors() {
+        // arrange
+        let app: Router = Router::from_services(&ServiceProvider::default());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/users").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
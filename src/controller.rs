@@ -0,0 +1,303 @@
+use crate::Inject;
+use axum::{
+    extract::{FromRequest, FromRequestParts},
+    response::IntoResponse,
+    routing::{MethodFilter, MethodRouter},
+    Router,
+};
+use di::Ref;
+use std::future::Future;
+use std::marker::PhantomData;
+
+/// Defines the behavior of a controller method that can be mapped to a route.
+///
+/// # Remarks
+///
+/// This trait is implemented for functions whose first parameter is a [`Ref`] of the controller, such as a method with a
+/// `self: Ref<Self>` receiver, followed by up to 15 extractors. The last extractor can consume the request body.
+pub trait Action<C: ?Sized, T, S>: Sized {
+    /// Maps the action to the specified method of a router.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - the [HTTP method](MethodFilter) the action is mapped to
+    /// * `router` - the [router](MethodRouter) the action is added to
+    fn on(self, filter: MethodFilter, router: MethodRouter<S>) -> MethodRouter<S>;
+}
+
+impl<C, F, Fut, Res, S> Action<C, (), S> for F
+where
+    C: ?Sized + Send + Sync + 'static,
+    F: Fn(Ref<C>) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = Res> + Send,
+    Res: IntoResponse,
+    S: Clone + Send + Sync + 'static,
+{
+    fn on(self, filter: MethodFilter, router: MethodRouter<S>) -> MethodRouter<S> {
+        router.on(filter, move |Inject(controller): Inject<C>| self(controller))
+    }
+}
+
+macro_rules! impl_action {
+    ([$($ty:ident),*], $last:ident) => {
+        #[allow(non_snake_case)]
+        impl<C, F, Fut, Res, S, M, $($ty,)* $last> Action<C, (M, $($ty,)* $last,), S> for F
+        where
+            C: ?Sized + Send + Sync + 'static,
+            F: Fn(Ref<C>, $($ty,)* $last) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future<Output = Res> + Send,
+            Res: IntoResponse,
+            S: Clone + Send + Sync + 'static,
+            M: 'static,
+            $($ty: FromRequestParts<S> + Send + 'static,)*
+            $last: FromRequest<S, M> + Send + 'static,
+        {
+            fn on(self, filter: MethodFilter, router: MethodRouter<S>) -> MethodRouter<S> {
+                router.on(filter, move |Inject(controller): Inject<C>, $($ty: $ty,)* $last: $last| {
+                    self(controller, $($ty,)* $last)
+                })
+            }
+        }
+    };
+}
+
+impl_action!([], T1);
+impl_action!([T1], T2);
+impl_action!([T1, T2], T3);
+impl_action!([T1, T2, T3], T4);
+impl_action!([T1, T2, T3, T4], T5);
+impl_action!([T1, T2, T3, T4, T5], T6);
+impl_action!([T1, T2, T3, T4, T5, T6], T7);
+impl_action!([T1, T2, T3, T4, T5, T6, T7], T8);
+impl_action!([T1, T2, T3, T4, T5, T6, T7, T8], T9);
+impl_action!([T1, T2, T3, T4, T5, T6, T7, T8, T9], T10);
+impl_action!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10], T11);
+impl_action!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11], T12);
+impl_action!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12], T13);
+impl_action!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13], T14);
+impl_action!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14], T15);
+
+/// Defines the behavior of a controller whose routes are known.
+///
+/// # Remarks
+///
+/// This trait is typically implemented by applying the `#[controller]` attribute to the `impl` block of a controller.
+/// The routes can be added to an application with [`Router::merge`].
+pub trait Controller: Send + Sync + 'static {
+    /// Gets the routes of the controller.
+    fn routes<S: Clone + Send + Sync + 'static>() -> Router<S>;
+}
+
+/// Represents the route of a controller whose [actions](Action) are mapped to HTTP methods.
+pub struct ControllerRoute<C: ?Sized, S = ()> {
+    router: MethodRouter<S>,
+    _controller: PhantomData<fn() -> Ref<C>>,
+}
+
+macro_rules! method {
+    ($name:ident, $filter:ident, $method:literal) => {
+        #[doc = concat!("Maps the specified action to the HTTP `", $method, "` method.")]
+        ///
+        /// # Arguments
+        ///
+        /// * `action` - the controller [action](Action) to map
+        pub fn $name<T>(self, action: impl Action<C, T, S>) -> Self {
+            self.on(MethodFilter::$filter, action)
+        }
+    };
+}
+
+impl<C, S> ControllerRoute<C, S>
+where
+    C: ?Sized + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            router: MethodRouter::new(),
+            _controller: PhantomData,
+        }
+    }
+
+    pub(crate) fn into_router(self) -> MethodRouter<S> {
+        self.router
+    }
+
+    /// Maps the specified action to the HTTP methods matched by a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - the [HTTP methods](MethodFilter) the action is mapped to
+    /// * `action` - the controller [action](Action) to map
+    pub fn on<T>(mut self, filter: MethodFilter, action: impl Action<C, T, S>) -> Self {
+        self.router = action.on(filter, self.router);
+        self
+    }
+
+    method!(delete, DELETE, "DELETE");
+    method!(get, GET, "GET");
+    method!(head, HEAD, "HEAD");
+    method!(options, OPTIONS, "OPTIONS");
+    method!(patch, PATCH, "PATCH");
+    method!(post, POST, "POST");
+    method!(put, PUT, "PUT");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::prelude::*;
+    use axum::{extract::Path, http::StatusCode, test_helpers::TestClient, Router};
+    use di::{injectable, Injectable, ServiceCollection, ServiceProvider};
+    use std::sync::Mutex;
+
+    trait UserRepository: Send + Sync {
+        fn find(&self, id: u32) -> Option<String>;
+        fn add(&self, name: String);
+        fn count(&self) -> usize;
+    }
+
+    #[injectable(UserRepository)]
+    struct InMemoryUserRepository {
+        users: Mutex<Vec<String>>,
+    }
+
+    impl UserRepository for InMemoryUserRepository {
+        fn find(&self, id: u32) -> Option<String> {
+            self.users.lock().unwrap().get(id as usize).cloned()
+        }
+
+        fn add(&self, name: String) {
+            self.users.lock().unwrap().push(name)
+        }
+
+        fn count(&self) -> usize {
+            self.users.lock().unwrap().len()
+        }
+    }
+
+    #[injectable]
+    struct UserController {
+        users: Ref<dyn UserRepository>,
+    }
+
+    impl UserController {
+        async fn list(self: Ref<Self>) -> String {
+            self.users.count().to_string()
+        }
+
+        async fn find(self: Ref<Self>, Path(id): Path<u32>) -> Result<String, StatusCode> {
+            self.users.find(id).ok_or(StatusCode::NOT_FOUND)
+        }
+
+        async fn create(self: Ref<Self>, name: String) -> StatusCode {
+            self.users.add(name);
+            StatusCode::CREATED
+        }
+    }
+
+    fn new_provider() -> ServiceProvider {
+        ServiceCollection::new()
+            .add(InMemoryUserRepository::singleton())
+            .add(UserController::scoped())
+            .build_provider()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn route_controller_should_invoke_actions() {
+        // arrange
+        let app = Router::new()
+            .route_controller::<UserController>("/users", |c| c.get(UserController::list).post(UserController::create))
+            .route_controller::<UserController>("/users/{id}", |c| c.get(UserController::find))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let created = client.post("/users").body("Bob").into_future().await.status();
+        let count = client.get("/users").into_future().await.text().await;
+        let found = client.get("/users/0").into_future().await.text().await;
+        let missing = client.get("/users/1").into_future().await.status();
+
+        // assert
+        assert_eq!(created, StatusCode::CREATED);
+        assert_eq!(&count, "1");
+        assert_eq!(&found, "Bob");
+        assert_eq!(missing, StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "macros")]
+    mod generated {
+        use super::*;
+        use crate::controller;
+
+        #[injectable]
+        pub struct AccountController {
+            users: Ref<dyn UserRepository>,
+        }
+
+        #[controller("/accounts")]
+        impl AccountController {
+            #[get("/")]
+            async fn list(self: Ref<Self>) -> String {
+                self.users.count().to_string()
+            }
+
+            #[post("/")]
+            async fn create(self: Ref<Self>, name: String) -> StatusCode {
+                self.users.add(name);
+                StatusCode::CREATED
+            }
+
+            #[get("/{id}")]
+            async fn find(self: Ref<Self>, Path(id): Path<u32>) -> Result<String, StatusCode> {
+                self.users.find(id).ok_or(StatusCode::NOT_FOUND)
+            }
+        }
+    }
+
+    #[cfg(feature = "macros")]
+    #[tokio::test]
+    async fn controller_attribute_should_generate_routes() {
+        // arrange
+        use generated::AccountController;
+
+        let provider = ServiceCollection::new()
+            .add(InMemoryUserRepository::singleton())
+            .add(AccountController::scoped())
+            .build_provider()
+            .unwrap();
+        let app = Router::new().merge(AccountController::routes()).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let created = client.post("/accounts").body("Alice").into_future().await.status();
+        let count = client.get("/accounts").into_future().await.text().await;
+        let found = client.get("/accounts/0").into_future().await.text().await;
+
+        // assert
+        assert_eq!(created, StatusCode::CREATED);
+        assert_eq!(&count, "1");
+        assert_eq!(&found, "Alice");
+    }
+
+    #[tokio::test]
+    async fn route_controller_should_fail_with_500_for_unregistered_controller() {
+        // arrange
+        let app = Router::new()
+            .route_controller::<UserController>("/users", |c| c.get(UserController::list))
+            .with_provider(ServiceProvider::default());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/users").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
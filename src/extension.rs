@@ -0,0 +1,81 @@
+use crate::scope;
+use axum::{extract::Request, middleware::Next, response::Response};
+use std::any::Any;
+
+/// Resolves a service from the request scope and inserts it into the request extensions, if it has been registered.
+pub(crate) async fn extension_middleware<T>(mut request: Request, next: Next) -> Response
+where
+    T: ?Sized + Any + Send + Sync,
+{
+    if let Some(service) = scope::from_extensions(request.extensions()).and_then(|services| services.get::<T>()) {
+        request.extensions_mut().insert(service);
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use crate::prelude::*;
+    use axum::{http::StatusCode, routing::get, test_helpers::TestClient, Extension, Router};
+    use di::{injectable, Injectable, ServiceCollection, ServiceProvider};
+    use std::sync::Arc;
+
+    trait Clock: Send + Sync {
+        fn now(&self) -> u64;
+    }
+
+    #[injectable(Clock)]
+    struct FixedClock;
+
+    impl Clock for FixedClock {
+        fn now(&self) -> u64 {
+            42
+        }
+    }
+
+    async fn handler(Extension(clock): Extension<Arc<dyn Clock>>) -> String {
+        clock.now().to_string()
+    }
+
+    #[tokio::test]
+    async fn service_should_be_exposed_as_extension() {
+        // arrange
+        let provider = ServiceCollection::new()
+            .add(FixedClock::singleton())
+            .build_provider()
+            .unwrap();
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_service_extension::<dyn Clock>()
+            .with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "42");
+    }
+
+    #[tokio::test]
+    async fn unregistered_service_should_not_be_exposed_as_extension() {
+        // arrange
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_service_extension::<dyn Clock>()
+            .with_provider(ServiceProvider::default());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,135 @@
+use di::ServiceProvider;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Represents a handle to a service provider that can be replaced at runtime.
+///
+/// # Remarks
+///
+/// A new scope is created from the current service provider of the handle for each request. When the service provider
+/// is replaced, requests that are in flight continue to use the scope created from the previous service provider. The
+/// singletons of the previous service provider are dropped after the last request that uses them has finished and all
+/// other references to the previous service provider have been dropped.
+#[derive(Clone)]
+pub struct ProviderHandle(Arc<RwLock<ServiceProvider>>);
+
+impl ProviderHandle {
+    /// Initializes a new service provider handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - the initial [service provider](ServiceProvider)
+    pub fn new(provider: ServiceProvider) -> Self {
+        Self(Arc::new(RwLock::new(provider)))
+    }
+
+    /// Gets the current service provider.
+    pub fn current(&self) -> ServiceProvider {
+        self.0.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Replaces the current service provider and returns the previous service provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - the new [service provider](ServiceProvider) used by subsequent requests
+    pub fn swap(&self, provider: ServiceProvider) -> ServiceProvider {
+        let mut current = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        std::mem::replace(&mut *current, provider)
+    }
+
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl From<ServiceProvider> for ProviderHandle {
+    fn from(provider: ServiceProvider) -> Self {
+        Self::new(provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use crate::{prelude::*, Inject};
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{existing_as_self, ServiceCollection};
+    use tokio::sync::Notify;
+
+    struct Version(&'static str, Arc<AtomicBool>);
+
+    impl Drop for Version {
+        fn drop(&mut self) {
+            self.1.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn new_provider(version: &'static str, dropped: &Arc<AtomicBool>) -> ServiceProvider {
+        ServiceCollection::new()
+            .add(existing_as_self(Version(version, dropped.clone())))
+            .build_provider()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn swapped_provider_should_be_used_by_new_requests() {
+        // arrange
+        async fn handler(Inject(version): Inject<Version>) -> String {
+            version.0.into()
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let handle = ProviderHandle::new(new_provider("1", &dropped));
+        let app = Router::new().route("/test", get(handler)).with_provider(handle.clone());
+
+        let client = TestClient::new(app);
+        let before = client.get("/test").into_future().await.text().await;
+
+        // act
+        handle.swap(new_provider("2", &dropped));
+
+        // assert
+        let after = client.get("/test").into_future().await.text().await;
+
+        assert_eq!(&before, "1");
+        assert_eq!(&after, "2");
+    }
+
+    #[tokio::test]
+    async fn previous_singletons_should_be_dropped_after_last_request() {
+        // arrange
+        static STARTED: Notify = Notify::const_new();
+        static RELEASED: Notify = Notify::const_new();
+
+        async fn slow(Inject(version): Inject<Version>) -> String {
+            STARTED.notify_one();
+            RELEASED.notified().await;
+            version.0.into()
+        }
+
+        let previous = Arc::new(AtomicBool::new(false));
+        let current = Arc::new(AtomicBool::new(false));
+        let handle = ProviderHandle::new(new_provider("1", &previous));
+        let app = Router::new().route("/slow", get(slow)).with_provider(handle.clone());
+        let client = TestClient::new(app);
+        let in_flight = tokio::spawn(client.get("/slow").into_future());
+
+        STARTED.notified().await;
+
+        // act
+        drop(handle.swap(new_provider("2", &current)));
+
+        // assert
+        assert!(!previous.load(Ordering::SeqCst));
+
+        RELEASED.notify_one();
+        let text = in_flight.await.unwrap().text().await;
+
+        assert_eq!(&text, "1");
+        assert!(previous.load(Ordering::SeqCst));
+        assert!(!current.load(Ordering::SeqCst));
+    }
+}
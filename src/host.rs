@@ -0,0 +1,427 @@
+use crate::{
+    background, middleware::BoxFuture, prelude::ServiceCollectionExt, startup, AppBuilder, Module, WarmUp, WarmUpError,
+};
+use di::{ServiceCollection, ValidationError};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinError;
+use tokio_util::sync::CancellationToken;
+
+/// Represents the possible errors that can occur while running a [web host](WebHost).
+#[derive(Debug)]
+pub enum HostError {
+    /// Indicates the services of the application are not valid.
+    Validation(ValidationError),
+
+    /// Indicates an I/O error occurred while binding or serving the application.
+    Io(io::Error),
+
+    /// Indicates one or more services could not be resolved while warming up the service provider.
+    WarmUp(WarmUpError),
+
+    /// Indicates the [startup task](crate::StartupTask) with the specified name failed.
+    Startup(String, Box<dyn Error + Send + Sync>),
+
+    /// Indicates a [background service](crate::BackgroundService) panicked.
+    Background(JoinError),
+}
+
+impl Display for HostError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            Self::Validation(error) => write!(f, "The application services are not valid. {}", error),
+            Self::Io(error) => write!(f, "The application could not be served. {}", error),
+            Self::WarmUp(error) => write!(f, "The application services could not be warmed up. {}", error),
+            Self::Startup(name, error) => write!(f, "The startup task '{}' failed. {}", name, error),
+            Self::Background(error) => write!(f, "A background service failed. {}", error),
+        }
+    }
+}
+
+impl Error for HostError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Validation(error) => Some(error),
+            Self::Io(error) => Some(error),
+            Self::WarmUp(error) => Some(error),
+            Self::Startup(_, error) => Some(error.as_ref()),
+            Self::Background(error) => Some(error),
+        }
+    }
+}
+
+impl From<ValidationError> for HostError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+impl From<WarmUpError> for HostError {
+    fn from(error: WarmUpError) -> Self {
+        Self::WarmUp(error)
+    }
+}
+
+impl From<JoinError> for HostError {
+    fn from(error: JoinError) -> Self {
+        Self::Background(error)
+    }
+}
+
+impl From<io::Error> for HostError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+enum Listen {
+    Address(String),
+    Listener(TcpListener),
+}
+
+/// Represents a host that serves an application composed of [modules](Module).
+pub struct WebHost {
+    app: AppBuilder,
+    listen: Listen,
+    shutdown: Option<BoxFuture<'static, ()>>,
+    shutdown_timeout: Duration,
+    warm_up: Option<WarmUp>,
+}
+
+impl Default for WebHost {
+    fn default() -> Self {
+        Self::new(AppBuilder::new())
+    }
+}
+
+impl From<AppBuilder> for WebHost {
+    fn from(app: AppBuilder) -> Self {
+        Self::new(app)
+    }
+}
+
+impl WebHost {
+    /// Initializes a new web host.
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - the [builder](AppBuilder) of the hosted application
+    ///
+    /// # Remarks
+    ///
+    /// The host listens on `127.0.0.1:5000` unless another address is specified.
+    pub fn new(app: AppBuilder) -> Self {
+        Self {
+            app,
+            listen: Listen::Address("127.0.0.1:5000".into()),
+            shutdown: None,
+            shutdown_timeout: Duration::from_secs(30),
+            warm_up: None,
+        }
+    }
+
+    /// Adds the specified module to the hosted application.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - the [module](Module) to add
+    pub fn module(mut self, module: impl Module + 'static) -> Self {
+        self.app = self.app.module(module);
+        self
+    }
+
+    /// Configures the services of the hosted application.
+    ///
+    /// # Arguments
+    ///
+    /// * `configure` - the function that adds services to the [service collection](ServiceCollection)
+    pub fn services<F: FnOnce(&mut ServiceCollection)>(mut self, configure: F) -> Self {
+        self.app = self.app.services(configure);
+        self
+    }
+
+    /// Sets the address the host listens on.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - the address to bind to, such as `0.0.0.0:8080`
+    pub fn bind(mut self, address: impl Into<String>) -> Self {
+        self.listen = Listen::Address(address.into());
+        self
+    }
+
+    /// Sets the listener the host accepts connections from.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - the bound [listener](TcpListener)
+    pub fn listener(mut self, listener: TcpListener) -> Self {
+        self.listen = Listen::Listener(listener);
+        self
+    }
+
+    /// Sets the signal that starts a graceful shutdown of the host.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal` - the future that completes when the host should shut down
+    ///
+    /// # Remarks
+    ///
+    /// When a signal is not specified, the host shuts down when SIGINT (Ctrl+C) or, on Unix, SIGTERM is received.
+    pub fn with_shutdown_signal(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
+        self.shutdown = Some(Box::pin(signal));
+        self
+    }
+
+    /// Sets the maximum amount of time to wait for background services to complete during shutdown.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - the maximum [duration](Duration) to wait
+    ///
+    /// # Remarks
+    ///
+    /// The [background services](crate::BackgroundService) that have not completed within the timeout are aborted.
+    /// The default timeout is 30 seconds.
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Resolves the specified services when the host starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `warm_up` - the [services](WarmUp) to resolve
+    ///
+    /// # Remarks
+    ///
+    /// The services are resolved before any [startup task](crate::StartupTask) runs so that a service that cannot be
+    /// resolved fails the host when it starts instead of when a route is first requested. A service that panics when
+    /// it is resolved is not caught and the panic is raised when the host starts.
+    pub fn with_warm_up(mut self, warm_up: WarmUp) -> Self {
+        self.warm_up = Some(warm_up);
+        self
+    }
+
+    /// Runs the host until it is shut down.
+    ///
+    /// # Remarks
+    ///
+    /// The [startup tasks](crate::StartupTask) are run in order before the host binds its address. The [background
+    /// services](crate::BackgroundService) are then started before the host starts accepting connections.
+    /// After the shutdown signal is received, the host stops accepting connections, cancels the background services,
+    /// and waits for in-flight requests and background services to complete. The root service provider is then
+    /// dropped so that singleton services are dropped after the last request has completed and before this function
+    /// returns. An error is returned if a background service panicked.
+    pub async fn run(self) -> Result<(), HostError> {
+        let Self {
+            app,
+            listen,
+            shutdown,
+            shutdown_timeout,
+            warm_up,
+        } = self;
+        let (services, provider, router) = app.build_parts()?;
+
+        if let Some(warm_up) = warm_up {
+            services.warm_up(&provider, warm_up)?;
+        }
+
+        startup::run(&provider).await?;
+
+        let listener = match listen {
+            Listen::Address(address) => TcpListener::bind(address).await?,
+            Listen::Listener(listener) => listener,
+        };
+        let shutdown = shutdown.unwrap_or_else(|| Box::pin(shutdown_signal()));
+        let token = CancellationToken::new();
+        let tasks = background::start(&services, &provider, &token);
+
+        drop(services);
+
+        let signal = {
+            let token = token.clone();
+            async move {
+                shutdown.await;
+                token.cancel();
+            }
+        };
+        let result = axum::serve(listener, router).with_graceful_shutdown(signal).await;
+
+        token.cancel();
+
+        let stopped = background::stop(tasks, shutdown_timeout).await;
+
+        // the router and its copies of the provider have been dropped; dropping the root provider drops the singletons
+        drop(provider);
+        result?;
+        Ok(stopped?)
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            terminate.recv().await;
+        } else {
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Inject;
+    use axum::{routing::get, Router};
+    use di::{injectable, singleton_factory, Injectable, Ref};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio::sync::oneshot;
+
+    struct Counter(Arc<AtomicBool>);
+
+    impl Drop for Counter {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    struct Counting(Arc<AtomicBool>);
+
+    impl Module for Counting {
+        fn configure_services(&self, services: &mut ServiceCollection) {
+            let dropped = self.0.clone();
+            services.add(singleton_factory(move |_| Ref::new(Counter(dropped.clone()))));
+        }
+
+        fn configure_routes(&self, router: Router) -> Router {
+            router.route("/", get(|_: Inject<Counter>| async { "Counted" }))
+        }
+    }
+
+    #[tokio::test]
+    async fn run_should_serve_until_shutdown_and_drop_singletons() {
+        // arrange
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (stop, stopped) = oneshot::channel::<()>();
+        let dropped = Arc::new(AtomicBool::new(false));
+        let host = WebHost::default()
+            .module(Counting(dropped.clone()))
+            .listener(listener)
+            .with_shutdown_signal(async move {
+                stopped.await.ok();
+            });
+        let running = tokio::spawn(host.run());
+
+        // act
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        let mut response = String::new();
+
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let dropped_while_running = dropped.load(Ordering::SeqCst);
+
+        stop.send(()).unwrap();
+        running.await.unwrap().unwrap();
+
+        // assert
+        assert!(response.ends_with("Counted"));
+        assert!(!dropped_while_running);
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn run_should_fail_for_invalid_services() {
+        // arrange
+        #[injectable]
+        struct Dependent {
+            _counter: Ref<Counter>,
+        }
+
+        let host = WebHost::default().services(|services| {
+            services.add(Dependent::singleton());
+        });
+
+        // act
+        let result = host.run().await;
+
+        // assert
+        assert!(matches!(result, Err(HostError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn run_should_warm_up_singletons() {
+        // arrange
+        struct Settings;
+
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = created.clone();
+        let host = WebHost::default()
+            .services(move |services| {
+                services.add(singleton_factory(move |_| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ref::new(Settings)
+                }));
+            })
+            .bind("127.0.0.1:0")
+            .with_shutdown_signal(async {})
+            .with_warm_up(WarmUp::Singletons);
+
+        // act
+        host.run().await.unwrap();
+
+        // assert
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn run_should_fail_for_services_that_cannot_be_warmed_up() {
+        // arrange
+        struct Settings;
+
+        let host = WebHost::default()
+            .services(|services| {
+                services.add(singleton_factory(|_| -> Ref<Settings> {
+                    panic!("The settings are not configured.")
+                }));
+            })
+            .bind("127.0.0.1:0")
+            .with_shutdown_signal(async {})
+            .with_warm_up(WarmUp::Singletons);
+
+        // act
+        let result = host.run().await;
+
+        // assert
+        assert!(matches!(result, Err(HostError::WarmUp(error)) if error.failures().len() == 1));
+    }
+}
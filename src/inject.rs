@@ -1,6 +1,13 @@
+use crate::{
+    inject_lock::{hand_out, LockError},
+    scope::services,
+};
 use axum::http::StatusCode;
-use axum::{extract::FromRequestParts, http::request::Parts};
-use di::{Ref, RefMut, ServiceProvider};
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::request::Parts,
+};
+use di::{Mut, Ref, RefMut, Type};
 use std::any::type_name;
 use std::convert::Infallible;
 
@@ -33,6 +40,24 @@ fn unregistered_type<T: ?Sized>() -> String {
     format!("No service for type '{}' has been registered.", type_name::<T>())
 }
 
+#[inline]
+fn unset_value(name: &str) -> String {
+    format!("The scoped value for type '{}' has not been set.", name)
+}
+
+#[inline]
+pub(crate) fn conflict(error: LockError) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+#[inline]
+pub(crate) fn missing_provider() -> (StatusCode, String) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "No service provider has been added to the request.".into(),
+    )
+}
+
 impl<T, S> FromRequestParts<S> for TryInject<T>
 where
     T: ?Sized + 'static,
@@ -41,8 +66,8 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            Ok(Self(provider.get::<T>()))
+        if let Some(services) = services(parts) {
+            Ok(Self(services.get::<T>()))
         } else {
             Ok(Self(None))
         }
@@ -57,9 +82,11 @@ where
     type Rejection = (StatusCode, String);
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            if let Some(service) = provider.get::<T>() {
-                return Ok(Self(service));
+        if let Some(services) = services(parts) {
+            match services.try_get::<T>() {
+                Ok(Some(service)) => return Ok(Self(service)),
+                Err(name) => return Err((StatusCode::INTERNAL_SERVER_ERROR, unset_value(name))),
+                Ok(None) => {}
             }
         }
 
@@ -75,8 +102,8 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            Ok(Self(provider.get_mut::<T>()))
+        if let Some(services) = services(parts) {
+            Ok(Self(services.get_mut::<T>()))
         } else {
             Ok(Self(None))
         }
@@ -91,8 +118,9 @@ where
     type Rejection = (StatusCode, String);
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            if let Some(service) = provider.get_mut::<T>() {
+        if let Some(services) = services(parts) {
+            if let Some(service) = services.get_mut::<T>() {
+                hand_out(parts, &service, Type::of::<Mut<T>>()).map_err(conflict)?;
                 return Ok(Self(service));
             }
         }
@@ -101,6 +129,43 @@ where
     }
 }
 
+impl<T, S> OptionalFromRequestParts<S> for Inject<T>
+where
+    T: ?Sized + 'static,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if let Some(services) = services(parts) {
+            Ok(services.get::<T>().map(Self))
+        } else {
+            Err(missing_provider())
+        }
+    }
+}
+
+impl<T, S> OptionalFromRequestParts<S> for InjectMut<T>
+where
+    T: ?Sized + 'static,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if let Some(services) = services(parts) {
+            if let Some(service) = services.get_mut::<T>() {
+                hand_out(parts, &service, Type::of::<Mut<T>>()).map_err(conflict)?;
+                return Ok(Some(Self(service)));
+            }
+
+            Ok(None)
+        } else {
+            Err(missing_provider())
+        }
+    }
+}
+
 impl<T, S> FromRequestParts<S> for InjectAll<T>
 where
     T: ?Sized + 'static,
@@ -109,8 +174,8 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            Ok(Self(provider.get_all::<T>().collect()))
+        if let Some(services) = services(parts) {
+            Ok(Self(services.get_all::<T>().collect()))
         } else {
             Ok(Self(Vec::new()))
         }
@@ -125,8 +190,8 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            Ok(Self(provider.get_all_mut::<T>().collect()))
+        if let Some(services) = services(parts) {
+            Ok(Self(services.get_all_mut::<T>().collect()))
         } else {
             Ok(Self(Vec::new()))
         }
@@ -145,7 +210,7 @@ mod tests {
         test_helpers::TestClient,
         Router,
     };
-    use di::{injectable, Injectable, ServiceCollection};
+    use di::{injectable, Injectable, ServiceCollection, ServiceProvider};
     use http::StatusCode;
 
     #[tokio::test]
@@ -430,4 +495,81 @@ mod tests {
         // assert
         assert_eq!(&text, "Test");
     }
+
+    #[tokio::test]
+    async fn optional_inject_into_handler_should_be_none_for_unregistered_service() {
+        // arrange
+        #[injectable]
+        struct Service;
+
+        async fn handler(service: Option<Inject<Service>>) -> StatusCode {
+            if service.is_some() {
+                StatusCode::OK
+            } else {
+                StatusCode::NO_CONTENT
+            }
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_provider(ServiceProvider::default());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn optional_inject_mut_into_handler() {
+        // arrange
+        #[injectable]
+        struct Counter(usize);
+
+        async fn handler(counter: Option<InjectMut<Counter>>) -> String {
+            let InjectMut(counter) = counter.unwrap();
+            let value = counter.read().unwrap().0;
+            value.to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(Counter::scoped().as_mut())
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/test", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "0");
+    }
+
+    #[tokio::test]
+    async fn optional_inject_should_fail_with_500_without_provider() {
+        // arrange
+        #[injectable]
+        struct Service;
+
+        async fn handler(_service: Option<Inject<Service>>) -> StatusCode {
+            StatusCode::NO_CONTENT
+        }
+
+        let app = Router::new().route("/test", get(handler));
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
 }
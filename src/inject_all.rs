@@ -0,0 +1,247 @@
+use crate::scope::services;
+use axum::{extract::FromRequestParts, http::request::Parts};
+use di::Ref;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+/// Defines the behavior of a service that is ordered by priority.
+pub trait Priority {
+    /// Gets the priority of the service.
+    ///
+    /// # Remarks
+    ///
+    /// Services with a lower priority are ordered first. Services with the same priority retain their registration
+    /// order.
+    fn priority(&self) -> i32;
+}
+
+/// Defines the behavior of a request-aware filter for a collection of services.
+pub trait ServiceFilter<T: ?Sized> {
+    /// Determines whether the service should be included for the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - the service to evaluate
+    /// * `parts` - the [parts](Parts) of the current request
+    fn matches(service: &T, parts: &Parts) -> bool;
+}
+
+/// Represents a container for a collection of injected services ordered by [priority](Priority).
+#[derive(Clone, Debug)]
+pub struct InjectAllOrdered<T: ?Sized>(pub Vec<Ref<T>>);
+
+/// Represents a container for a collection of injected services which match a [filter](ServiceFilter).
+///
+/// # Remarks
+///
+/// The matching services retain their registration order.
+#[derive(Clone, Debug)]
+pub struct InjectAllWhere<T: ?Sized, F>(pub Vec<Ref<T>>, pub PhantomData<F>);
+
+/// Represents a container for a collection of injected services which match a [filter](ServiceFilter) and are
+/// ordered by [priority](Priority).
+#[derive(Clone, Debug)]
+pub struct InjectAllOrderedWhere<T: ?Sized, F>(pub Vec<Ref<T>>, pub PhantomData<F>);
+
+fn resolve<T: ?Sized + 'static>(parts: &Parts, filter: impl Fn(&T, &Parts) -> bool) -> Vec<Ref<T>> {
+    if let Some(services) = services(parts) {
+        services.get_all::<T>().filter(|s| filter(s, parts)).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+fn order<T: ?Sized + Priority>(mut services: Vec<Ref<T>>) -> Vec<Ref<T>> {
+    services.sort_by_key(|s| s.priority());
+    services
+}
+
+impl<T, S> FromRequestParts<S> for InjectAllOrdered<T>
+where
+    T: ?Sized + Priority + 'static,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(order(resolve(parts, |_, _| true))))
+    }
+}
+
+impl<T, F, S> FromRequestParts<S> for InjectAllWhere<T, F>
+where
+    T: ?Sized + 'static,
+    F: ServiceFilter<T>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(resolve(parts, F::matches), PhantomData))
+    }
+}
+
+impl<T, F, S> FromRequestParts<S> for InjectAllOrderedWhere<T, F>
+where
+    T: ?Sized + Priority + 'static,
+    F: ServiceFilter<T>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(order(resolve(parts, F::matches)), PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::prelude::*;
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, Injectable, ServiceCollection, ServiceProvider};
+
+    trait Step: Priority + Send + Sync {
+        fn name(&self) -> &str;
+        fn admin_only(&self) -> bool {
+            false
+        }
+    }
+
+    #[injectable(Step)]
+    struct Validate;
+
+    #[injectable(Step)]
+    struct Enrich;
+
+    #[injectable(Step)]
+    struct Audit;
+
+    impl Priority for Validate {
+        fn priority(&self) -> i32 {
+            1
+        }
+    }
+
+    impl Priority for Enrich {
+        fn priority(&self) -> i32 {
+            2
+        }
+    }
+
+    impl Priority for Audit {
+        fn priority(&self) -> i32 {
+            0
+        }
+    }
+
+    impl Step for Validate {
+        fn name(&self) -> &str {
+            "validate"
+        }
+    }
+
+    impl Step for Enrich {
+        fn name(&self) -> &str {
+            "enrich"
+        }
+    }
+
+    impl Step for Audit {
+        fn name(&self) -> &str {
+            "audit"
+        }
+
+        fn admin_only(&self) -> bool {
+            true
+        }
+    }
+
+    struct AdminRoutes;
+
+    impl ServiceFilter<dyn Step> for AdminRoutes {
+        fn matches(service: &dyn Step, parts: &Parts) -> bool {
+            !service.admin_only() || parts.uri.path().starts_with("/admin")
+        }
+    }
+
+    fn names(steps: Vec<Ref<dyn Step>>) -> String {
+        steps.iter().map(|s| s.name()).collect::<Vec<_>>().join(",")
+    }
+
+    fn new_provider() -> ServiceProvider {
+        ServiceCollection::new()
+            .try_add_to_all(Enrich::scoped())
+            .try_add_to_all(Audit::scoped())
+            .try_add_to_all(Validate::scoped())
+            .build_provider()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn inject_all_ordered_into_handler() {
+        // arrange
+        async fn handler(InjectAllOrdered(steps): InjectAllOrdered<dyn Step>) -> String {
+            names(steps)
+        }
+
+        let app = Router::new().route("/test", get(handler)).with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "audit,validate,enrich");
+    }
+
+    #[tokio::test]
+    async fn inject_all_where_into_handler() {
+        // arrange
+        async fn handler(InjectAllWhere(steps, _): InjectAllWhere<dyn Step, AdminRoutes>) -> String {
+            names(steps)
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .route("/admin/test", get(handler))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let public = client.get("/test").into_future().await.text().await;
+        let admin = client.get("/admin/test").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&public, "enrich,validate");
+        assert_eq!(&admin, "enrich,audit,validate");
+    }
+
+    #[tokio::test]
+    async fn inject_all_ordered_where_into_handler() {
+        // arrange
+        async fn handler(InjectAllOrderedWhere(steps, _): InjectAllOrderedWhere<dyn Step, AdminRoutes>) -> String {
+            names(steps)
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .route("/admin/test", get(handler))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let public = client.get("/test").into_future().await.text().await;
+        let admin = client.get("/admin/test").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&public, "validate,enrich");
+        assert_eq!(&admin, "audit,validate,enrich");
+    }
+}
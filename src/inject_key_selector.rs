@@ -0,0 +1,431 @@
+use crate::scope::services;
+use axum::http::StatusCode;
+use axum::{
+    extract::{FromRequestParts, RawPathParams},
+    http::request::Parts,
+};
+use di::{Ref, ServiceProvider};
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{OnceLock, RwLock};
+
+/// Represents the source of a service key in a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeySource {
+    /// Indicates the key is the path parameter with the specified name.
+    Path(&'static str),
+
+    /// Indicates the key is the value of the header with the specified name.
+    Header(&'static str),
+
+    /// Indicates the key is the query parameter with the specified name.
+    Query(&'static str),
+}
+
+impl KeySource {
+    async fn value<S: Send + Sync>(&self, parts: &mut Parts, state: &S) -> Option<String> {
+        match self {
+            Self::Path(name) => RawPathParams::from_request_parts(parts, state)
+                .await
+                .ok()?
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_owned()),
+            Self::Header(name) => parts
+                .headers
+                .get(*name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned),
+            Self::Query(name) => form_urlencoded::parse(parts.uri.query()?.as_bytes())
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned()),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Path(name) | Self::Header(name) | Self::Query(name) => name,
+        }
+    }
+}
+
+type Resolve<TSvc> = fn(&ServiceProvider) -> Option<Ref<TSvc>>;
+
+/// Represents a map of key names to keyed services.
+pub struct KeyMap<TSvc: ?Sized> {
+    entries: Vec<(&'static str, Resolve<TSvc>)>,
+}
+
+impl<TSvc: ?Sized + 'static> KeyMap<TSvc> {
+    /// Initializes a new, empty key map.
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Maps the specified name to the service registered with a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the key as it appears in a request
+    pub fn insert<TKey>(mut self, name: &'static str) -> Self {
+        self.entries.push((name, resolve_by_key::<TKey, TSvc>));
+        self
+    }
+
+    fn get(&self, name: &str) -> Option<Resolve<TSvc>> {
+        self.entries
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, resolve)| *resolve)
+    }
+}
+
+impl<TSvc: ?Sized + 'static> Default for KeyMap<TSvc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn resolve_by_key<TKey, TSvc: ?Sized + 'static>(provider: &ServiceProvider) -> Option<Ref<TSvc>> {
+    provider.get_by_key::<TKey, TSvc>().map(Ref::from)
+}
+
+/// Defines the behavior of a selector that chooses a keyed service from the request at runtime.
+pub trait KeySelector<TSvc: ?Sized> {
+    /// Gets the source of the key in the request.
+    fn source() -> KeySource;
+
+    /// Gets the map of key names to keyed services.
+    ///
+    /// # Remarks
+    ///
+    /// The map is only created the first time a key is selected and is then cached for the lifetime of the process.
+    fn keys() -> KeyMap<TSvc>;
+}
+
+type KeyMaps = RwLock<HashMap<(TypeId, TypeId), &'static (dyn Any + Send + Sync)>>;
+
+fn key_map<TSel, TSvc>() -> &'static KeyMap<TSvc>
+where
+    TSel: KeySelector<TSvc> + 'static,
+    TSvc: ?Sized + 'static,
+{
+    static MAPS: OnceLock<KeyMaps> = OnceLock::new();
+
+    let maps = MAPS.get_or_init(Default::default);
+    let id = (TypeId::of::<TSel>(), TypeId::of::<KeyMap<TSvc>>());
+    let cached = maps.read().unwrap_or_else(|e| e.into_inner()).get(&id).copied();
+    let map = match cached {
+        Some(map) => map,
+        None => *maps
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(id)
+            // each selector is only leaked once, which bounds the cache by the number of selector types
+            .or_insert_with(|| Box::leak(Box::new(TSel::keys()))),
+    };
+
+    map.downcast_ref().expect("The key map does not match its selector.")
+}
+
+/// Represents a container for a required, injected, keyed service whose key is selected from the request.
+///
+/// # Remarks
+///
+/// The HTTP request short-circuits with HTTP status code 400 - Bad Request when the key is not present in the request,
+/// HTTP status code 404 - Not Found when the key is not mapped by the selector, and HTTP status code 500 - Internal
+/// Server Error when the mapped service has not been registered.
+#[derive(Clone, Debug)]
+pub struct InjectWithKeySelector<TSel, TSvc: ?Sized + 'static>(pub Ref<TSvc>, pub PhantomData<TSel>);
+
+impl<TSel, TSvc, S> FromRequestParts<S> for InjectWithKeySelector<TSel, TSvc>
+where
+    TSel: KeySelector<TSvc> + 'static,
+    TSvc: ?Sized + 'static,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let source = TSel::source();
+        let Some(key) = source.value(parts, state).await else {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("The key '{}' was not found in the request.", source.name()),
+            ));
+        };
+        let Some(resolve) = key_map::<TSel, TSvc>().get(&key) else {
+            return Err((StatusCode::NOT_FOUND, format!("The key '{}' is not recognized.", key)));
+        };
+
+        if let Some(services) = services(parts) {
+            if let Some(service) = services.first(resolve) {
+                return Ok(Self(service, PhantomData));
+            }
+        }
+
+        Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!(
+                "No service for type '{}' with the key '{}' has been registered.",
+                type_name::<TSvc>(),
+                key
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::prelude::*;
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, Injectable, ServiceCollection};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    mod key {
+        pub struct Stripe;
+        pub struct Adyen;
+        pub struct PayPal;
+    }
+
+    trait PaymentGateway: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    #[injectable(PaymentGateway)]
+    struct StripeGateway;
+
+    #[injectable(PaymentGateway)]
+    struct AdyenGateway;
+
+    impl PaymentGateway for StripeGateway {
+        fn name(&self) -> &str {
+            "Stripe"
+        }
+    }
+
+    impl PaymentGateway for AdyenGateway {
+        fn name(&self) -> &str {
+            "Adyen"
+        }
+    }
+
+    fn gateways() -> KeyMap<dyn PaymentGateway> {
+        KeyMap::new()
+            .insert::<key::Stripe>("stripe")
+            .insert::<key::Adyen>("adyen")
+            .insert::<key::PayPal>("paypal")
+    }
+
+    struct ByPath;
+
+    impl KeySelector<dyn PaymentGateway> for ByPath {
+        fn source() -> KeySource {
+            KeySource::Path("provider")
+        }
+
+        fn keys() -> KeyMap<dyn PaymentGateway> {
+            gateways()
+        }
+    }
+
+    struct ByHeader;
+
+    impl KeySelector<dyn PaymentGateway> for ByHeader {
+        fn source() -> KeySource {
+            KeySource::Header("x-payment-provider")
+        }
+
+        fn keys() -> KeyMap<dyn PaymentGateway> {
+            gateways()
+        }
+    }
+
+    struct ByQuery;
+
+    impl KeySelector<dyn PaymentGateway> for ByQuery {
+        fn source() -> KeySource {
+            KeySource::Query("provider")
+        }
+
+        fn keys() -> KeyMap<dyn PaymentGateway> {
+            gateways()
+        }
+    }
+
+    fn new_provider() -> ServiceProvider {
+        ServiceCollection::new()
+            .add(StripeGateway::scoped().with_key::<key::Stripe>())
+            .add(AdyenGateway::scoped().with_key::<key::Adyen>())
+            .build_provider()
+            .unwrap()
+    }
+
+    async fn by_path(InjectWithKeySelector(gateway, _): InjectWithKeySelector<ByPath, dyn PaymentGateway>) -> String {
+        gateway.name().into()
+    }
+
+    #[tokio::test]
+    async fn inject_with_key_selected_from_path_into_handler() {
+        // arrange
+        let app = Router::new()
+            .route("/payments/{provider}", get(by_path))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let stripe = client.get("/payments/stripe").into_future().await.text().await;
+        let adyen = client.get("/payments/adyen").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&stripe, "Stripe");
+        assert_eq!(&adyen, "Adyen");
+    }
+
+    #[tokio::test]
+    async fn inject_with_key_selected_from_header_into_handler() {
+        // arrange
+        async fn handler(
+            InjectWithKeySelector(gateway, _): InjectWithKeySelector<ByHeader, dyn PaymentGateway>,
+        ) -> String {
+            gateway.name().into()
+        }
+
+        let app = Router::new()
+            .route("/payments", get(handler))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client
+            .get("/payments")
+            .header("x-payment-provider", "adyen")
+            .into_future()
+            .await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Adyen");
+    }
+
+    #[tokio::test]
+    async fn inject_with_key_selected_from_query_into_handler() {
+        // arrange
+        async fn handler(
+            InjectWithKeySelector(gateway, _): InjectWithKeySelector<ByQuery, dyn PaymentGateway>,
+        ) -> String {
+            gateway.name().into()
+        }
+
+        let app = Router::new()
+            .route("/payments", get(handler))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/payments?provider=stripe").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Stripe");
+    }
+
+    #[tokio::test]
+    async fn request_should_fail_with_400_for_missing_key() {
+        // arrange
+        async fn handler(
+            InjectWithKeySelector(gateway, _): InjectWithKeySelector<ByQuery, dyn PaymentGateway>,
+        ) -> String {
+            gateway.name().into()
+        }
+
+        let app = Router::new()
+            .route("/payments", get(handler))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/payments").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn request_should_fail_with_404_for_unknown_key() {
+        // arrange
+        let app = Router::new()
+            .route("/payments/{provider}", get(by_path))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/payments/square").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn request_should_fail_with_500_for_unregistered_service_with_selected_key() {
+        // arrange
+        let app = Router::new()
+            .route("/payments/{provider}", get(by_path))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/payments/paypal").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn key_map_should_be_created_once() {
+        // arrange
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counted;
+
+        impl KeySelector<dyn PaymentGateway> for Counted {
+            fn source() -> KeySource {
+                KeySource::Path("provider")
+            }
+
+            fn keys() -> KeyMap<dyn PaymentGateway> {
+                CREATED.fetch_add(1, Ordering::SeqCst);
+                gateways()
+            }
+        }
+
+        async fn handler(
+            InjectWithKeySelector(gateway, _): InjectWithKeySelector<Counted, dyn PaymentGateway>,
+        ) -> String {
+            gateway.name().into()
+        }
+
+        let app = Router::new()
+            .route("/payments/{provider}", get(handler))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let _ = client.get("/payments/stripe").into_future().await;
+        let _ = client.get("/payments/adyen").into_future().await;
+
+        // assert
+        assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+    }
+}
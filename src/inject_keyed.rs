@@ -1,6 +1,12 @@
+use crate::inject::{conflict, missing_provider};
+use crate::inject_lock::hand_out;
+use crate::scope::services;
 use axum::http::StatusCode;
-use axum::{extract::FromRequestParts, http::request::Parts};
-use di::{KeyedRef, KeyedRefMut, ServiceProvider};
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::request::Parts,
+};
+use di::{KeyedRef, KeyedRefMut, Mut, Type};
 use std::any::type_name;
 use std::convert::Infallible;
 
@@ -45,8 +51,8 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            Ok(Self(provider.get_by_key::<TKey, TSvc>()))
+        if let Some(services) = services(parts) {
+            Ok(Self(services.get_by_key::<TKey, TSvc>()))
         } else {
             Ok(Self(None))
         }
@@ -61,8 +67,8 @@ where
     type Rejection = (StatusCode, String);
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            if let Some(service) = provider.get_by_key::<TKey, TSvc>() {
+        if let Some(services) = services(parts) {
+            if let Some(service) = services.get_by_key::<TKey, TSvc>() {
                 return Ok(Self(service));
             }
         }
@@ -82,8 +88,8 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            Ok(Self(provider.get_by_key_mut::<TKey, TSvc>()))
+        if let Some(services) = services(parts) {
+            Ok(Self(services.get_by_key_mut::<TKey, TSvc>()))
         } else {
             Ok(Self(None))
         }
@@ -98,8 +104,9 @@ where
     type Rejection = (StatusCode, String);
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            if let Some(service) = provider.get_by_key_mut::<TKey, TSvc>() {
+        if let Some(services) = services(parts) {
+            if let Some(service) = services.get_by_key_mut::<TKey, TSvc>() {
+                hand_out(parts, &service, Type::keyed::<TKey, Mut<TSvc>>()).map_err(conflict)?;
                 return Ok(Self(service));
             }
         }
@@ -111,6 +118,43 @@ where
     }
 }
 
+impl<TKey, TSvc, S> OptionalFromRequestParts<S> for InjectWithKey<TKey, TSvc>
+where
+    TSvc: ?Sized + 'static,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if let Some(services) = services(parts) {
+            Ok(services.get_by_key::<TKey, TSvc>().map(Self))
+        } else {
+            Err(missing_provider())
+        }
+    }
+}
+
+impl<TKey, TSvc, S> OptionalFromRequestParts<S> for InjectWithKeyMut<TKey, TSvc>
+where
+    TSvc: ?Sized + 'static,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if let Some(services) = services(parts) {
+            if let Some(service) = services.get_by_key_mut::<TKey, TSvc>() {
+                hand_out(parts, &service, Type::keyed::<TKey, Mut<TSvc>>()).map_err(conflict)?;
+                return Ok(Some(Self(service)));
+            }
+
+            Ok(None)
+        } else {
+            Err(missing_provider())
+        }
+    }
+}
+
 impl<TKey, TSvc, S> FromRequestParts<S> for InjectAllWithKey<TKey, TSvc>
 where
     TSvc: ?Sized + 'static,
@@ -119,8 +163,8 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            Ok(Self(provider.get_all_by_key::<TKey, TSvc>().collect()))
+        if let Some(services) = services(parts) {
+            Ok(Self(services.get_all_by_key::<TKey, TSvc>().collect()))
         } else {
             Ok(Self(Vec::new()))
         }
@@ -135,8 +179,8 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(provider) = parts.extensions.get::<ServiceProvider>() {
-            Ok(Self(provider.get_all_by_key_mut::<TKey, TSvc>().collect()))
+        if let Some(services) = services(parts) {
+            Ok(Self(services.get_all_by_key_mut::<TKey, TSvc>().collect()))
         } else {
             Ok(Self(Vec::new()))
         }
@@ -155,7 +199,7 @@ mod tests {
         test_helpers::TestClient,
         Router,
     };
-    use di::{injectable, Injectable, ServiceCollection};
+    use di::{injectable, Injectable, ServiceCollection, ServiceProvider};
     use http::StatusCode;
 
     mod key {
@@ -337,4 +381,45 @@ mod tests {
         // assert
         assert_eq!(&text, "Test");
     }
+
+    #[tokio::test]
+    async fn optional_inject_with_key_into_handler() {
+        // arrange
+        trait Service: Send + Sync {
+            fn do_work(&self) -> String;
+        }
+
+        #[injectable(Service)]
+        struct ServiceImpl;
+
+        impl Service for ServiceImpl {
+            fn do_work(&self) -> String {
+                "Test".into()
+            }
+        }
+
+        async fn handler(
+            basic: Option<InjectWithKey<key::Basic, dyn Service>>,
+            advanced: Option<InjectWithKey<key::Advanced, dyn Service>>,
+        ) -> String {
+            let basic = basic.map(|InjectWithKey(service)| service.do_work());
+            format!("{:?},{}", basic, advanced.is_some())
+        }
+
+        let provider = ServiceCollection::new()
+            .add(ServiceImpl::scoped().with_key::<key::Basic>())
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/test", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Some(\"Test\"),false");
+    }
 }
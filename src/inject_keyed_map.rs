@@ -0,0 +1,230 @@
+use crate::scope::services;
+use axum::{extract::FromRequestParts, http::request::Parts};
+use di::{singleton, Ref, ServiceDescriptor, ServiceProvider};
+use std::any::Any;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+/// Defines the behavior of a service key with a name.
+pub trait KeyName {
+    /// Gets the name of the key.
+    const NAME: &'static str;
+}
+
+/// Represents the error that occurs when a keyed service is added with a descriptor that is not keyed by its key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyMismatchError {
+    service: &'static str,
+    key: &'static str,
+}
+
+impl KeyMismatchError {
+    /// Initializes a new key mismatch error.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - the name of the service type
+    /// * `key` - the name of the key type
+    pub(crate) fn new(service: &'static str, key: &'static str) -> Self {
+        Self { service, key }
+    }
+
+    /// Gets the name of the service type.
+    pub fn service(&self) -> &str {
+        self.service
+    }
+
+    /// Gets the name of the key type.
+    pub fn key(&self) -> &str {
+        self.key
+    }
+}
+
+impl Display for KeyMismatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            f,
+            "The descriptor for '{}' is not keyed by '{}'.",
+            self.service, self.key
+        )
+    }
+}
+
+impl Error for KeyMismatchError {}
+
+/// Represents an entry in the map of keyed services for a service type.
+pub(crate) struct KeyedEntry<TSvc: ?Sized> {
+    name: &'static str,
+    resolve: fn(&ServiceProvider) -> Option<Ref<TSvc>>,
+}
+
+fn resolve_by_key<TKey, TSvc: ?Sized + 'static>(provider: &ServiceProvider) -> Option<Ref<TSvc>> {
+    provider.get_by_key::<TKey, TSvc>().map(Ref::from)
+}
+
+/// Creates a descriptor for an entry in the map of keyed services.
+///
+/// # Arguments
+///
+/// * `name` - the name the keyed service is mapped to
+pub(crate) fn keyed_entry<TKey: 'static, TSvc: ?Sized + Any + Send + Sync>(name: &'static str) -> ServiceDescriptor {
+    // the key is used as the implementation type so that each key can be added to all entries only once
+    singleton::<KeyedEntry<TSvc>, TKey>().from(move |_| {
+        Ref::new(KeyedEntry {
+            name,
+            resolve: resolve_by_key::<TKey, TSvc>,
+        })
+    })
+}
+
+/// Represents a container for a map of injected, keyed services by key name.
+///
+/// # Remarks
+///
+/// Only keyed services registered with
+/// [`add_keyed`](crate::prelude::ServiceCollectionExt::add_keyed) or
+/// [`add_named_keyed`](crate::prelude::ServiceCollectionExt::add_named_keyed) are included in the map.
+#[derive(Clone, Debug)]
+pub struct InjectKeyedMap<T: ?Sized>(pub HashMap<&'static str, Ref<T>>);
+
+impl<T, S> FromRequestParts<S> for InjectKeyedMap<T>
+where
+    T: ?Sized + Any + Send + Sync,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mut map = HashMap::new();
+
+        if let Some(services) = services(parts) {
+            for entry in services.get_all::<KeyedEntry<T>>() {
+                if let Some(service) = services.first(entry.resolve) {
+                    map.insert(entry.name, service);
+                }
+            }
+        }
+
+        Ok(Self(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::prelude::*;
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, Injectable, ServiceCollection};
+
+    mod key {
+        use super::KeyName;
+
+        pub struct Csv;
+        pub struct Json;
+
+        impl KeyName for Json {
+            const NAME: &'static str = "json";
+        }
+    }
+
+    trait Exporter: Send + Sync {
+        fn extension(&self) -> &str;
+    }
+
+    #[injectable(Exporter)]
+    struct CsvExporter;
+
+    #[injectable(Exporter)]
+    struct JsonExporter;
+
+    impl Exporter for CsvExporter {
+        fn extension(&self) -> &str {
+            ".csv"
+        }
+    }
+
+    impl Exporter for JsonExporter {
+        fn extension(&self) -> &str {
+            ".json"
+        }
+    }
+
+    #[tokio::test]
+    async fn inject_keyed_map_into_handler() {
+        // arrange
+        async fn handler(InjectKeyedMap(exporters): InjectKeyedMap<dyn Exporter>) -> String {
+            let mut names: Vec<_> = exporters
+                .iter()
+                .map(|(name, exporter)| format!("{}={}", name, exporter.extension()))
+                .collect();
+
+            names.sort();
+            names.join(",")
+        }
+
+        let mut services = ServiceCollection::new();
+
+        services
+            .add_keyed::<key::Csv, dyn Exporter>(CsvExporter::scoped().with_key::<key::Csv>())
+            .unwrap()
+            .add_named_keyed::<key::Json, dyn Exporter>(JsonExporter::scoped().with_key::<key::Json>())
+            .unwrap();
+
+        let provider = services.build_provider().unwrap();
+        let app = Router::new().route("/test", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "di_axum::inject_keyed_map::tests::key::Csv=.csv,json=.json");
+    }
+
+    #[tokio::test]
+    async fn inject_keyed_map_should_be_empty_without_keyed_services() {
+        // arrange
+        async fn handler(InjectKeyedMap(exporters): InjectKeyedMap<dyn Exporter>) -> String {
+            exporters.len().to_string()
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_provider(ServiceProvider::default());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "0");
+    }
+
+    #[test]
+    fn add_keyed_should_fail_for_descriptor_with_another_key() {
+        // arrange
+        let mut services = ServiceCollection::new();
+
+        // act
+        let result = services.add_keyed::<key::Json, dyn Exporter>(CsvExporter::scoped().with_key::<key::Csv>());
+
+        // assert
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            format!(
+                "The descriptor for '{}' is not keyed by '{}'.",
+                std::any::type_name::<dyn Exporter>(),
+                std::any::type_name::<key::Json>()
+            )
+        );
+        assert_eq!(services.len(), 0);
+    }
+}
@@ -0,0 +1,810 @@
+use crate::scope::services;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{extract::FromRequestParts, http::request::Parts};
+use di::{Mut, Ref, ServiceCollection, ServiceDescriptor, ServiceLifetime, Type};
+use std::any::type_name;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter, Result as FormatResult};
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock};
+
+/// Represents the maximum amount of time to wait while acquiring a lock on a mutable service.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LockTimeout(pub Duration);
+
+/// Represents the possible errors that can occur when acquiring a lock on a mutable, injected service.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LockError {
+    /// Indicates the service has not been registered.
+    Unregistered(&'static str),
+
+    /// Indicates the lock could not be acquired within the configured timeout.
+    TimedOut(&'static str, Duration),
+
+    /// Indicates acquiring the lock can deadlock with another lock of the same request.
+    ///
+    /// # Remarks
+    ///
+    /// A lock acquired by the [`InjectRead`] and [`InjectWrite`] extractors conflicts when it is already held by the
+    /// same request or when another request acquired the locks in the opposite order. The chain lists the locks held
+    /// by the request, in the order they were acquired, followed by the lock that was requested.
+    ///
+    /// A mutable service handed out by the [`InjectMut`](crate::InjectMut) and
+    /// [`InjectWithKeyMut`](crate::InjectWithKeyMut) extractors conflicts with another mutable service handed out to
+    /// the same handler or middleware when both are the same instance or both depend on the same scoped or singleton
+    /// mutable service. A middleware is only distinguished from the handler when it is created with
+    /// [`from_fn_with_services`](crate::middleware::from_fn_with_services). The
+    /// shared dependencies are only known when they have been captured with
+    /// [`add_lock_tracking`](crate::prelude::ServiceCollectionExt::add_lock_tracking). The chain lists both services
+    /// and the dependency they share.
+    ///
+    /// Detection is only performed in debug builds.
+    Deadlock(Vec<String>),
+}
+
+impl Display for LockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            Self::Unregistered(name) => write!(f, "No service for type '{}' has been registered.", name),
+            Self::TimedOut(name, timeout) => write!(
+                f,
+                "The lock for service '{}' could not be acquired within {:?}.",
+                name, timeout
+            ),
+            Self::Deadlock(chain) => write!(f, "A deadlock was detected acquiring locks: {}.", chain.join(" -> ")),
+        }
+    }
+}
+
+impl Error for LockError {}
+
+impl IntoResponse for LockError {
+    fn into_response(self) -> Response {
+        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+}
+
+impl Display for Access {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        f.write_str(match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        })
+    }
+}
+
+type Alive = Arc<dyn Fn() -> bool + Send + Sync>;
+
+struct Acquisition {
+    id: usize,
+    address: usize,
+    name: &'static str,
+    access: Access,
+    alive: Alive,
+}
+
+struct Handle {
+    frame: usize,
+    address: usize,
+    service: Type,
+    shared: Vec<Type>,
+}
+
+#[derive(Default)]
+struct Acquisitions {
+    next_id: usize,
+    frame: usize,
+    items: Vec<Acquisition>,
+    handles: Vec<Handle>,
+}
+
+/// Represents the order in which two locks have been acquired by any request.
+struct Order {
+    before: usize,
+    after: usize,
+    alive: [Alive; 2],
+}
+
+// the order is shared by all requests; a lock that has been dropped is no longer ordered
+static ORDERS: Mutex<Vec<Order>> = Mutex::new(Vec::new());
+
+/// Gets the name of the service stored by the specified mutable service type, if it is mutable.
+fn mutable(service: &Type) -> Option<&str> {
+    let name = type_name::<Mut<()>>();
+    let prefix = &name[..name.len() - "()>".len()];
+
+    Type::deconstruct(service).0.strip_prefix(prefix)?.strip_suffix('>')
+}
+
+/// Represents the shared, mutable dependencies of each mutable service in a service collection.
+#[derive(Default)]
+pub(crate) struct LockGraph(HashMap<Type, Vec<Type>>);
+
+impl LockGraph {
+    /// Captures the shared, mutable dependencies of each mutable service.
+    ///
+    /// # Arguments
+    ///
+    /// * `services` - the [service collection](ServiceCollection) to capture the dependencies from
+    ///
+    /// # Remarks
+    ///
+    /// A dependency is shared when it is a scoped or singleton mutable service, which means it is the same instance
+    /// for every service in a request that depends on it directly or transitively. A mutable service that is scoped
+    /// or singleton is shared with itself.
+    pub(crate) fn new(services: &ServiceCollection) -> Self {
+        // the last descriptor of a service type is the one that is resolved
+        let descriptors: HashMap<&Type, &ServiceDescriptor> = services.iter().map(|d| (d.service_type(), d)).collect();
+        let shared = |service: &Type| {
+            mutable(service).is_some()
+                && descriptors
+                    .get(service)
+                    .is_some_and(|d| d.lifetime() != ServiceLifetime::Transient)
+        };
+        let mut graph = HashMap::new();
+
+        for &service in descriptors.keys().filter(|&&service| mutable(service).is_some()) {
+            let mut visited = HashSet::from([service]);
+            let mut pending = vec![service];
+            let mut dependencies: Vec<Type> = Vec::new();
+
+            if shared(service) {
+                dependencies.push(service.clone());
+            }
+
+            while let Some(current) = pending.pop() {
+                if let Some(descriptor) = descriptors.get(current) {
+                    for dependency in descriptor.dependencies().iter().map(|d| d.injected_type()) {
+                        if visited.insert(dependency) {
+                            if shared(dependency) {
+                                dependencies.push(dependency.clone());
+                            }
+
+                            pending.push(dependency);
+                        }
+                    }
+                }
+            }
+
+            graph.insert(service.clone(), dependencies);
+        }
+
+        Self(graph)
+    }
+}
+
+/// Represents a tracker of the locks held by a single request.
+#[derive(Clone, Default)]
+pub(crate) struct LockTracker(Arc<Mutex<Acquisitions>>);
+
+struct Ticket {
+    tracker: LockTracker,
+    id: usize,
+}
+
+fn describe(service: &Type, shared: &Type) -> String {
+    let name = mutable(service).unwrap_or(service.name());
+
+    if service == shared {
+        format!("mut '{}'", name)
+    } else {
+        format!("mut '{}' via '{}'", name, mutable(shared).unwrap_or(shared.name()))
+    }
+}
+
+impl LockTracker {
+    fn enter<T>(&self, lock: &Ref<RwLock<T>>, access: Access) -> Result<usize, LockError>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let address = Ref::as_ptr(lock) as *const () as usize;
+        let name = type_name::<T>();
+        let mut acquisitions = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut orders = ORDERS.lock().unwrap_or_else(PoisonError::into_inner);
+        let chain = |acquisitions: &Acquisitions| -> Vec<String> {
+            acquisitions
+                .items
+                .iter()
+                .map(|a| format!("{} '{}'", a.access, a.name))
+                .chain(std::iter::once(format!("{} '{}'", access, name)))
+                .collect()
+        };
+
+        // a read lock waits behind a pending writer so acquiring the same lock twice can deadlock regardless of access
+        if acquisitions.items.iter().any(|a| a.address == address) {
+            return Err(LockError::Deadlock(chain(&acquisitions)));
+        }
+
+        orders.retain(|o| o.alive.iter().all(|alive| alive()));
+
+        // another request that acquired the requested lock before a held lock waits for the held lock
+        if let Some(held) = acquisitions
+            .items
+            .iter()
+            .find(|a| orders.iter().any(|o| o.before == address && o.after == a.address))
+        {
+            let mut chain = chain(&acquisitions);
+            chain.push(format!("{} '{}'", held.access, held.name));
+            return Err(LockError::Deadlock(chain));
+        }
+
+        let weak = Ref::downgrade(lock);
+        let alive: Alive = Arc::new(move || weak.strong_count() > 0);
+
+        for held in &acquisitions.items {
+            if !orders.iter().any(|o| o.before == held.address && o.after == address) {
+                orders.push(Order {
+                    before: held.address,
+                    after: address,
+                    alive: [held.alive.clone(), alive.clone()],
+                });
+            }
+        }
+
+        let id = acquisitions.next_id;
+
+        acquisitions.next_id += 1;
+        acquisitions.items.push(Acquisition {
+            id,
+            address,
+            name,
+            access,
+            alive,
+        });
+
+        Ok(id)
+    }
+
+    fn hand_out(&self, address: usize, service: Type, shared: Vec<Type>) -> Result<(), LockError> {
+        let mut acquisitions = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let frame = acquisitions.frame;
+
+        for handle in acquisitions.handles.iter().filter(|h| h.frame == frame) {
+            if handle.address == address {
+                return Err(LockError::Deadlock(vec![
+                    describe(&handle.service, &handle.service),
+                    describe(&service, &service),
+                ]));
+            }
+
+            if let Some(dependency) = handle.shared.iter().find(|&d| shared.contains(d)) {
+                return Err(LockError::Deadlock(vec![
+                    describe(&handle.service, dependency),
+                    describe(&service, dependency),
+                ]));
+            }
+        }
+
+        acquisitions.handles.push(Handle {
+            frame,
+            address,
+            service,
+            shared,
+        });
+
+        Ok(())
+    }
+
+    /// Begins handing out mutable services to the next middleware or handler.
+    ///
+    /// # Remarks
+    ///
+    /// The mutable services handed out to a middleware are not compared with the mutable services handed out to the
+    /// middleware or handler that runs after it.
+    pub(crate) fn next_frame(&self) {
+        let mut acquisitions = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        acquisitions.frame += 1;
+    }
+
+    fn exit(&self, id: usize) {
+        let mut acquisitions = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        acquisitions.items.retain(|a| a.id != id);
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        self.tracker.exit(self.id);
+    }
+}
+
+/// Represents an owned, shared read guard over a mutable service.
+pub struct ReadGuard<T: ?Sized + 'static> {
+    guard: OwnedRwLockReadGuard<T>,
+    _ticket: Option<Ticket>,
+}
+
+/// Represents an owned, exclusive write guard over a mutable service.
+pub struct WriteGuard<T: ?Sized + 'static> {
+    guard: OwnedRwLockWriteGuard<T>,
+    _ticket: Option<Ticket>,
+}
+
+impl<T: ?Sized + 'static> Deref for ReadGuard<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized + 'static> Deref for WriteGuard<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized + 'static> DerefMut for WriteGuard<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<T: ?Sized + Debug + 'static> Debug for ReadGuard<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        self.guard.fmt(f)
+    }
+}
+
+impl<T: ?Sized + Debug + 'static> Debug for WriteGuard<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        self.guard.fmt(f)
+    }
+}
+
+/// Represents a container for a required, mutable, injected service that is locked for reading.
+///
+/// # Remarks
+///
+/// The service must be registered as a [`tokio::sync::RwLock<T>`], which is a different storage model than a mutable
+/// service registered with `as_mut` and injected as a [`RefMut<T>`](di::RefMut). A service registered as a
+/// [`RefMut<T>`](di::RefMut) cannot be injected by this extractor and other services must depend on the lock as a
+/// [`Ref<RwLock<T>>`](di::Ref). The lock is acquired asynchronously and, unlike a [`RefMut<T>`](di::RefMut), is never
+/// poisoned when a holder panics.
+#[derive(Debug)]
+pub struct InjectRead<T: ?Sized + 'static>(pub ReadGuard<T>);
+
+/// Represents a container for a required, mutable, injected service that is locked for writing.
+///
+/// # Remarks
+///
+/// The service must be registered as a [`tokio::sync::RwLock<T>`], which is a different storage model than a mutable
+/// service registered with `as_mut` and injected as a [`RefMut<T>`](di::RefMut). A service registered as a
+/// [`RefMut<T>`](di::RefMut) cannot be injected by this extractor and other services must depend on the lock as a
+/// [`Ref<RwLock<T>>`](di::Ref). The lock is acquired asynchronously and, unlike a [`RefMut<T>`](di::RefMut), is never
+/// poisoned when a holder panics.
+#[derive(Debug)]
+pub struct InjectWrite<T: ?Sized + 'static>(pub WriteGuard<T>);
+
+async fn acquire<G>(parts: &Parts, name: &'static str, lock: impl Future<Output = G>) -> Result<G, LockError> {
+    match parts.extensions.get::<LockTimeout>() {
+        Some(&LockTimeout(timeout)) => tokio::time::timeout(timeout, lock)
+            .await
+            .map_err(|_| LockError::TimedOut(name, timeout)),
+        None => Ok(lock.await),
+    }
+}
+
+fn resolve<T: ?Sized + Send + Sync + 'static>(parts: &Parts) -> Result<Ref<RwLock<T>>, LockError> {
+    if let Some(services) = services(parts) {
+        if let Some(service) = services.get::<RwLock<T>>() {
+            return Ok(service);
+        }
+    }
+
+    Err(LockError::Unregistered(type_name::<RwLock<T>>()))
+}
+
+fn track<T>(parts: &Parts, lock: &Ref<RwLock<T>>, access: Access) -> Result<Option<Ticket>, LockError>
+where
+    T: ?Sized + Send + Sync + 'static,
+{
+    if let Some(tracker) = parts.extensions.get::<LockTracker>() {
+        Ok(Some(Ticket {
+            tracker: tracker.clone(),
+            id: tracker.enter(lock, access)?,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Records a mutable service that is handed out to a handler.
+///
+/// # Arguments
+///
+/// * `parts` - the [parts](Parts) of the current request
+/// * `service` - the mutable service handed out to the handler
+/// * `service_type` - the [type](Type) the mutable service is registered as
+///
+/// # Remarks
+///
+/// Mutable services are only recorded in debug builds. A mutable service conflicts with another mutable service
+/// handed out to the same handler or middleware when both are the same instance or both depend on the same shared,
+/// mutable service. A middleware is only distinguished from the handler when it is created with
+/// [`from_fn_with_services`](crate::middleware::from_fn_with_services).
+pub(crate) fn hand_out<T: ?Sized>(parts: &Parts, service: &Mut<T>, service_type: Type) -> Result<(), LockError> {
+    if let Some(tracker) = parts.extensions.get::<LockTracker>() {
+        let address = service as *const Mut<T> as *const () as usize;
+        let shared = services(parts)
+            .and_then(|services| services.get::<LockGraph>())
+            .and_then(|graph| graph.0.get(&service_type).cloned())
+            .unwrap_or_default();
+
+        tracker.hand_out(address, service_type, shared)
+    } else {
+        Ok(())
+    }
+}
+
+impl<T, S> FromRequestParts<S> for InjectRead<T>
+where
+    T: ?Sized + Send + Sync + 'static,
+    S: Send + Sync,
+{
+    type Rejection = LockError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let lock = resolve::<T>(parts)?;
+        let ticket = track(parts, &lock, Access::Read)?;
+        let guard = acquire(parts, type_name::<T>(), lock.read_owned()).await?;
+
+        Ok(Self(ReadGuard { guard, _ticket: ticket }))
+    }
+}
+
+impl<T, S> FromRequestParts<S> for InjectWrite<T>
+where
+    T: ?Sized + Send + Sync + 'static,
+    S: Send + Sync,
+{
+    type Rejection = LockError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let lock = resolve::<T>(parts)?;
+        let ticket = track(parts, &lock, Access::Write)?;
+        let guard = acquire(parts, type_name::<T>(), lock.write_owned()).await?;
+
+        Ok(Self(WriteGuard { guard, _ticket: ticket }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::{prelude::*, InjectMut};
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, scoped_factory, singleton_factory, Injectable, RefMut, ServiceCollection};
+
+    struct Counter(usize);
+
+    #[cfg(debug_assertions)]
+    struct Other(usize);
+
+    #[injectable]
+    struct SharedCounter(usize);
+
+    #[injectable]
+    struct SharedCounterA {
+        shared: RefMut<SharedCounter>,
+    }
+
+    #[cfg(debug_assertions)]
+    #[injectable]
+    struct SharedCounterB {
+        shared: RefMut<SharedCounter>,
+    }
+
+    #[injectable]
+    struct SimpleCounter(usize);
+
+    fn counter() -> Ref<RwLock<Counter>> {
+        Ref::new(RwLock::new(Counter(0)))
+    }
+
+    #[tokio::test]
+    async fn inject_write_into_handler() {
+        // arrange
+        async fn handler(InjectWrite(mut counter): InjectWrite<Counter>) -> String {
+            counter.0 += 1;
+            counter.0.to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(singleton_factory(|_| counter()))
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/count", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let _ = client.get("/count").into_future().await;
+        let response = client.get("/count").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "2");
+    }
+
+    #[tokio::test]
+    async fn inject_read_into_handler() {
+        // arrange
+        async fn handler(InjectRead(counter): InjectRead<Counter>) -> String {
+            counter.0.to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(scoped_factory(|_| counter()))
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/count", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/count").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "0");
+    }
+
+    #[tokio::test]
+    async fn request_should_fail_with_500_for_unregistered_lock() {
+        // arrange
+        async fn handler(InjectRead(counter): InjectRead<Counter>) -> String {
+            counter.0.to_string()
+        }
+
+        let provider = ServiceCollection::new().build_provider().unwrap();
+        let app = Router::new().route("/count", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/count").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.text().await.contains("RwLock"));
+    }
+
+    #[tokio::test]
+    async fn request_should_fail_with_500_when_lock_times_out() {
+        // arrange
+        async fn handler(InjectRead(counter): InjectRead<Counter>) -> String {
+            counter.0.to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(singleton_factory(|_| counter()))
+            .build_provider()
+            .unwrap();
+        let _guard = provider.get_required::<RwLock<Counter>>().write_owned().await;
+
+        let app = Router::new()
+            .route("/count", get(handler))
+            .with_provider(provider)
+            .with_lock_timeout(Duration::from_millis(10));
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/count").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.text().await.contains("could not be acquired"));
+    }
+
+    #[tokio::test]
+    #[cfg(debug_assertions)]
+    async fn request_should_fail_with_500_for_reentrant_write_lock() {
+        // arrange
+        async fn handler(InjectWrite(a): InjectWrite<Counter>, InjectWrite(b): InjectWrite<Counter>) -> String {
+            (a.0 + b.0).to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(scoped_factory(|_| counter()))
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/count", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/count").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response
+            .text()
+            .await
+            .contains("write 'di_axum::inject_lock::tests::Counter' -> write"));
+    }
+
+    #[tokio::test]
+    #[cfg(debug_assertions)]
+    async fn request_should_fail_with_500_for_reentrant_read_lock() {
+        // arrange
+        async fn handler(InjectRead(a): InjectRead<Counter>, InjectRead(b): InjectRead<Counter>) -> String {
+            (a.0 + b.0).to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(scoped_factory(|_| counter()))
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/count", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/count").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response
+            .text()
+            .await
+            .contains("read 'di_axum::inject_lock::tests::Counter' -> read"));
+    }
+
+    #[tokio::test]
+    #[cfg(debug_assertions)]
+    async fn request_should_fail_with_500_for_mutable_services_with_shared_dependency() {
+        // arrange
+        async fn handler(InjectMut(a): InjectMut<SharedCounterA>, InjectMut(b): InjectMut<SharedCounterB>) -> String {
+            let a = a.read().unwrap().shared.read().unwrap().0;
+            let b = b.read().unwrap().shared.read().unwrap().0;
+            (a + b).to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(SharedCounter::scoped().as_mut())
+            .add(SharedCounterA::transient().as_mut())
+            .add(SharedCounterB::transient().as_mut())
+            .add_lock_tracking()
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/count", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/count").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            response.text().await,
+            "A deadlock was detected acquiring locks: \
+             mut 'di_axum::inject_lock::tests::SharedCounterA' via 'di_axum::inject_lock::tests::SharedCounter' -> \
+             mut 'di_axum::inject_lock::tests::SharedCounterB' via 'di_axum::inject_lock::tests::SharedCounter'."
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(debug_assertions)]
+    async fn request_should_fail_with_500_for_same_mutable_service() {
+        // arrange
+        async fn handler(InjectMut(a): InjectMut<SharedCounter>, InjectMut(b): InjectMut<SharedCounter>) -> String {
+            (a.read().unwrap().0 + b.read().unwrap().0).to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(SharedCounter::scoped().as_mut())
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/count", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/count").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response
+            .text()
+            .await
+            .contains("mut 'di_axum::inject_lock::tests::SharedCounter' -> mut"));
+    }
+
+    #[tokio::test]
+    async fn inject_mutable_services_without_shared_dependency_into_handler() {
+        // arrange
+        async fn handler(InjectMut(a): InjectMut<SharedCounterA>, InjectMut(b): InjectMut<SimpleCounter>) -> String {
+            a.write().unwrap().shared.write().unwrap().0 += 1;
+            b.write().unwrap().0 += 1;
+            let a = a.read().unwrap().shared.read().unwrap().0;
+            let b = b.read().unwrap().0;
+            (a + b).to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(SharedCounter::scoped().as_mut())
+            .add(SharedCounterA::transient().as_mut())
+            .add(SimpleCounter::transient().as_mut())
+            .add_lock_tracking()
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/count", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/count").into_future().await;
+
+        // assert
+        assert_eq!(response.text().await, "2");
+    }
+
+    #[tokio::test]
+    #[cfg(debug_assertions)]
+    async fn request_should_fail_with_500_for_locks_acquired_in_opposite_order() {
+        // arrange
+        async fn forward(InjectWrite(a): InjectWrite<Counter>, InjectWrite(b): InjectWrite<Other>) -> String {
+            (a.0 + b.0).to_string()
+        }
+
+        async fn backward(InjectWrite(b): InjectWrite<Other>, InjectWrite(a): InjectWrite<Counter>) -> String {
+            (a.0 + b.0).to_string()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(singleton_factory(|_| counter()))
+            .add(singleton_factory(|_| Ref::new(RwLock::new(Other(0)))))
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new()
+            .route("/forward", get(forward))
+            .route("/backward", get(backward))
+            .with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let first = client.get("/forward").into_future().await;
+        let second = client.get("/backward").into_future().await;
+
+        // assert
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(second.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            second.text().await,
+            "A deadlock was detected acquiring locks: \
+             write 'di_axum::inject_lock::tests::Other' -> \
+             write 'di_axum::inject_lock::tests::Counter' -> \
+             write 'di_axum::inject_lock::tests::Other'."
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::scope::services;
+use axum::{extract::FromRequestParts, http::request::Parts};
+use di::Ref;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+/// Defines the behavior of a fallback for a service that has not been registered.
+pub trait Fallback<T: ?Sized> {
+    /// Creates and returns the fallback service.
+    fn fallback() -> Ref<T>;
+}
+
+impl<T: Default> Fallback<T> for T {
+    #[inline]
+    fn fallback() -> Ref<T> {
+        Ref::new(T::default())
+    }
+}
+
+/// Represents a container for an injected service, which uses a [fallback](Fallback) when the service has not been
+/// registered.
+#[derive(Clone, Debug)]
+pub struct InjectOr<T: ?Sized, D>(pub Ref<T>, pub PhantomData<D>);
+
+/// Represents a container for an injected service, which uses the [default](Default) value when the service has not
+/// been registered.
+#[derive(Clone, Debug)]
+pub struct InjectOrDefault<T>(pub Ref<T>);
+
+impl<T, D, S> FromRequestParts<S> for InjectOr<T, D>
+where
+    T: ?Sized + 'static,
+    D: Fallback<T>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let service = services(parts)
+            .and_then(|services| services.get::<T>())
+            .unwrap_or_else(D::fallback);
+
+        Ok(Self(service, PhantomData))
+    }
+}
+
+impl<T, S> FromRequestParts<S> for InjectOrDefault<T>
+where
+    T: Default + 'static,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let service = services(parts)
+            .and_then(|services| services.get::<T>())
+            .unwrap_or_else(T::fallback);
+
+        Ok(Self(service))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::prelude::*;
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, Injectable, ServiceCollection, ServiceProvider};
+
+    trait Cache: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    struct NullCache;
+
+    impl Cache for NullCache {
+        fn name(&self) -> String {
+            "Null".into()
+        }
+    }
+
+    impl Fallback<dyn Cache> for NullCache {
+        fn fallback() -> Ref<dyn Cache> {
+            Ref::new(NullCache)
+        }
+    }
+
+    #[injectable(Cache)]
+    struct MemoryCache;
+
+    impl Cache for MemoryCache {
+        fn name(&self) -> String {
+            "Memory".into()
+        }
+    }
+
+    async fn handler(InjectOr(cache, _): InjectOr<dyn Cache, NullCache>) -> String {
+        cache.name()
+    }
+
+    #[tokio::test]
+    async fn inject_or_should_use_fallback_for_unregistered_service() {
+        // arrange
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_provider(ServiceProvider::default());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Null");
+    }
+
+    #[tokio::test]
+    async fn inject_or_should_use_registered_service() {
+        // arrange
+        let provider = ServiceCollection::new()
+            .add(MemoryCache::singleton())
+            .build_provider()
+            .unwrap();
+
+        let app = Router::new().route("/test", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Memory");
+    }
+
+    #[tokio::test]
+    async fn inject_or_default_should_use_default_for_unregistered_service() {
+        // arrange
+        #[derive(Default)]
+        struct Settings {
+            retries: usize,
+        }
+
+        async fn handler(InjectOrDefault(settings): InjectOrDefault<Settings>) -> String {
+            settings.retries.to_string()
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_provider(ServiceProvider::default());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "0");
+    }
+}
@@ -1,49 +1,348 @@
 #![doc = include_str!("../README.md")]
 
+// allows the generated code to refer to this crate by name
+extern crate self as di_axum;
+
+mod app;
+#[cfg(feature = "host")]
+mod background;
+mod child;
+mod collection;
+mod context;
+mod contributor;
+mod controller;
+mod extension;
+mod handle;
+#[cfg(feature = "host")]
+mod host;
 mod inject;
+mod inject_all;
+mod inject_key_selector;
 mod inject_keyed;
+mod inject_keyed_map;
+mod inject_lock;
+mod inject_or;
+/// Contains middleware that can inject services.
+pub mod middleware;
+mod scope;
+mod scoped_value;
+#[cfg(feature = "host")]
+mod startup;
+mod tenant;
+mod warm_up;
+
+// used by the generated code; not part of the public api
+#[doc(hidden)]
+pub mod __private {
+    pub use axum::Router;
+}
+
+pub use app::{AppBuilder, Module};
+#[cfg(feature = "host")]
+pub use background::BackgroundService;
+pub use child::ParentProvider;
+pub use context::RequestContext;
+pub use contributor::RouteContributor;
+pub use controller::{Action, Controller, ControllerRoute};
 
+/// Generates the routes of a controller from the attributes of the methods in an `impl` block.
+///
+/// # Remarks
+///
+/// Each method with a `#[delete]`, `#[get]`, `#[head]`, `#[options]`, `#[patch]`, `#[post]`, or `#[put]` attribute is
+/// mapped to the path of the attribute, which is relative to the optional path of the controller. The methods are
+/// invoked on an instance of the controller resolved from the scope of each request and all remaining parameters are
+/// extractors.
+#[cfg(feature = "macros")]
+pub use di_axum_macros::controller;
+pub use handle::ProviderHandle;
+#[cfg(feature = "host")]
+pub use host::{HostError, WebHost};
 pub use inject::{Inject, InjectAll, InjectAllMut, InjectMut, TryInject, TryInjectMut};
+pub use inject_all::{InjectAllOrdered, InjectAllOrderedWhere, InjectAllWhere, Priority, ServiceFilter};
+pub use inject_key_selector::{InjectWithKeySelector, KeyMap, KeySelector, KeySource};
 pub use inject_keyed::{
     InjectAllWithKey, InjectAllWithKeyMut, InjectWithKey, InjectWithKeyMut, TryInjectWithKey, TryInjectWithKeyMut,
 };
+pub use inject_keyed_map::{InjectKeyedMap, KeyMismatchError, KeyName};
+pub use inject_lock::{InjectRead, InjectWrite, LockError, ReadGuard, WriteGuard};
+pub use inject_or::{Fallback, InjectOr, InjectOrDefault};
+pub use scope::{ScopeCollision, Stacking};
+pub use scoped_value::{ScopedValue, ScopedValues};
+#[cfg(feature = "host")]
+pub use startup::StartupTask;
+pub use tenant::{TenantProviders, TenantResolver};
+pub use warm_up::{WarmUp, WarmUpError};
 
 /// Contains library prelude.
 pub mod prelude {
+    pub use crate::collection::ServiceCollectionExt;
+    pub use crate::scoped_value::RequestExt;
+
+    use crate::{
+        child::{self, child_middleware},
+        contributor,
+        extension::extension_middleware,
+        inject_lock::LockTimeout,
+        middleware::{self, from_fn_in_scope, Middleware},
+        scope,
+        tenant::{tenant_middleware, Tenancy},
+        ControllerRoute, ProviderHandle, Stacking, TenantProviders, TenantResolver,
+    };
     use axum::{
         extract::{Request, State},
-        middleware::{from_fn_with_state, Next},
+        middleware::{from_fn, from_fn_with_state, Next},
         response::Response,
-        Router,
+        Extension, Router,
     };
-    use di::ServiceProvider;
-
-    async fn services_middleware(
-        State(provider): State<ServiceProvider>,
-        mut request: Request,
-        next: Next,
-    ) -> Response {
-        request.extensions_mut().insert(provider.create_scope());
+    use di::{ServiceCollection, ServiceProvider, ValidationError};
+    use std::any::Any;
+    use std::time::Duration;
+
+    #[derive(Clone)]
+    struct Layered {
+        provider: ProviderHandle,
+        stacking: Option<Stacking>,
+    }
+
+    impl Layered {
+        fn new(provider: ProviderHandle, stacking: Option<Stacking>) -> Self {
+            Self { provider, stacking }
+        }
+    }
+
+    async fn services_middleware(State(layer): State<Layered>, mut request: Request, next: Next) -> Response {
+        // a scope created from the same service provider by a middleware is shared with the handler
+        if !scope::is_origin(&request, &layer.provider) {
+            // the stacking behavior is intentional when it is explicitly specified
+            #[cfg(debug_assertions)]
+            if layer.stacking.is_none() {
+                scope::collide(&mut request);
+            }
+
+            scope::stack(
+                &mut request,
+                &layer.provider.current(),
+                layer.stacking.unwrap_or_default(),
+            );
+        }
+
         next.run(request).await
     }
 
     /// Provides [router][Router] extension methods.
     pub trait RouterExt: Sized {
+        /// Gets the type of state used by the router.
+        type State: Clone + Send + Sync + 'static;
+
+        /// Creates a router from the routes of all registered route contributors.
+        ///
+        /// # Arguments
+        ///
+        /// * `provider` - the [service provider][ServiceProvider] used to resolve the route contributors
+        ///
+        /// # Remarks
+        ///
+        /// The routes of each [`RouteContributor`](crate::RouteContributor) are merged in the order they were
+        /// registered and the service provider is added to the router. The router is empty if no route contributors
+        /// have been registered.
+        fn from_services(provider: &ServiceProvider) -> Self;
+
         /// Adds the specified service provider to a router.
         ///
         /// # Arguments
         ///
-        /// * `provider` - the [service provider][ServiceProvider] applied to the router
+        /// * `provider` - the [service provider][ServiceProvider] or [handle][ProviderHandle] applied to the router
         ///
         /// # Remarks
         ///
-        /// The service provider should be added after all routes are defined in the same manner as middleware.
-        fn with_provider(self, provider: ServiceProvider) -> Self;
+        /// The service provider should be added after all routes are defined in the same manner as middleware. A
+        /// [handle][ProviderHandle] can be used to replace the service provider at runtime. When a scope has already
+        /// been added to a request, the existing scope is [replaced](Stacking::Replace) and a
+        /// [collision](crate::ScopeCollision) is recorded in the request extensions in debug builds. Use
+        /// [`with_provider_stacking`](RouterExt::with_provider_stacking) to specify the behavior explicitly.
+        fn with_provider(self, provider: impl Into<ProviderHandle>) -> Self;
+
+        /// Adds the specified service provider to a router with the behavior used when a scope has already been added.
+        ///
+        /// # Arguments
+        ///
+        /// * `provider` - the [service provider][ServiceProvider] or [handle][ProviderHandle] applied to the router
+        /// * `stacking` - the [stacking][Stacking] behavior used when a scope has already been added to a request
+        ///
+        /// # Remarks
+        ///
+        /// A scope has already been added to a request when the router is nested in or merged into another router with
+        /// a service provider. [`with_provider`](RouterExt::with_provider) is equivalent to this method with
+        /// [`Stacking::Replace`], except that it records a [collision](crate::ScopeCollision) in debug builds.
+        fn with_provider_stacking(self, provider: impl Into<ProviderHandle>, stacking: Stacking) -> Self;
+
+        /// Adds the specified service providers for each tenant to a router.
+        ///
+        /// # Arguments
+        ///
+        /// * `resolver` - the [resolver][TenantResolver] used to determine the tenant of a request
+        /// * `providers` - the [service providers][TenantProviders] for each tenant
+        ///
+        /// # Remarks
+        ///
+        /// Each request creates a new scope from the service provider of its tenant. The tenant service providers
+        /// should be added after all routes are defined in the same manner as middleware.
+        fn with_tenant_providers(self, resolver: TenantResolver, providers: TenantProviders) -> Self;
+
+        /// Adds the specified child services to a router.
+        ///
+        /// # Arguments
+        ///
+        /// * `configure` - the function that adds the child services to a [service collection][ServiceCollection]
+        ///
+        /// # Remarks
+        ///
+        /// The child services are layered over the service provider applied by an outer router, if any. A service is
+        /// resolved from the child services first and then from the parent service provider. A collection of services
+        /// contains the parent services followed by the child services. A child service can depend on a service of
+        /// its parent that has been added with
+        /// [`add_parent_service`](crate::prelude::ServiceCollectionExt::add_parent_service) or resolve it using the
+        /// [`ParentProvider`](crate::ParentProvider).
+        ///
+        ///
+        /// The HTTP request short-circuits with HTTP status code 500 - Internal Server Error if a parent service has
+        /// been added and the router is not nested in a router with a service provider.
+        ///
+        /// # Panics
+        ///
+        /// The child services are not valid, such as when a child service depends on a parent service that has not
+        /// been added with [`add_parent_service`](crate::prelude::ServiceCollectionExt::add_parent_service). Use
+        /// [`try_with_child_services`](RouterExt::try_with_child_services) to handle the error instead.
+        fn with_child_services<F: FnOnce(&mut ServiceCollection)>(self, configure: F) -> Self;
+
+        /// Adds the specified child services to a router or returns an error if they are not valid.
+        ///
+        /// # Arguments
+        ///
+        /// * `configure` - the function that adds the child services to a [service collection][ServiceCollection]
+        ///
+        /// # Remarks
+        ///
+        /// This method is equivalent to [`with_child_services`](RouterExt::with_child_services), except that a
+        /// [validation error][ValidationError] is returned instead of panicking when the child services are not valid.
+        fn try_with_child_services<F: FnOnce(&mut ServiceCollection)>(
+            self,
+            configure: F,
+        ) -> Result<Self, ValidationError>;
+
+        /// Sets the maximum amount of time to wait while acquiring a lock on a mutable, injected service.
+        ///
+        /// # Arguments
+        ///
+        /// * `timeout` - the maximum [duration][Duration] to wait for a lock
+        ///
+        /// # Remarks
+        ///
+        /// The timeout applies to the [`InjectRead`](crate::InjectRead) and [`InjectWrite`](crate::InjectWrite)
+        /// extractors. When a timeout is not set, the extractors wait until the lock is acquired.
+        fn with_lock_timeout(self, timeout: Duration) -> Self;
+
+        /// Exposes a service resolved from the request scope as a request extension.
+        ///
+        /// # Remarks
+        ///
+        /// The service is inserted into the request extensions as a [`Ref<T>`](di::Ref) so that it can be extracted
+        /// with [`Extension`] by handlers and libraries that are not aware of dependency injection. The service is only
+        /// inserted when it has been registered. The service must be exposed before the service provider is added in
+        /// the same manner as middleware.
+        fn with_service_extension<T: ?Sized + Any + Send + Sync>(self) -> Self;
+
+        /// Adds middleware that is resolved from the scope of each request.
+        ///
+        /// # Remarks
+        ///
+        /// The [middleware][Middleware] must be registered as a service and must be added before the service provider
+        /// in the same manner as [`route_layer`](Router::route_layer). The HTTP request short-circuits with HTTP status
+        /// code 500 - Internal Server Error if the middleware has not been registered or a scope has not been added to
+        /// the request when the middleware runs.
+        fn layer_from_services<T: ?Sized + Middleware + 'static>(self) -> Self;
+
+        /// Adds a route whose HTTP methods are mapped to the actions of a controller.
+        ///
+        /// # Arguments
+        ///
+        /// * `path` - the path of the route
+        /// * `configure` - the function that maps the [controller route][ControllerRoute] to its actions
+        ///
+        /// # Remarks
+        ///
+        /// The controller must be registered as a service and is resolved from the scope of each request, which means
+        /// its dependencies do not need to be injected into each action. The HTTP request short-circuits with HTTP
+        /// status code 500 - Internal Server Error if the controller has not been registered.
+        fn route_controller<C: ?Sized + Send + Sync + 'static>(
+            self,
+            path: &str,
+            configure: impl FnOnce(ControllerRoute<C, Self::State>) -> ControllerRoute<C, Self::State>,
+        ) -> Self;
     }
 
     impl<S: Clone + Send + Sync + 'static> RouterExt for Router<S> {
-        fn with_provider(self, provider: ServiceProvider) -> Self {
-            self.route_layer(from_fn_with_state(provider, services_middleware))
+        type State = S;
+
+        fn from_services(provider: &ServiceProvider) -> Self {
+            let router: Self = contributor::merge(provider);
+
+            if router.has_routes() {
+                router.with_provider(provider.clone())
+            } else {
+                router
+            }
+        }
+
+        fn with_provider(self, provider: impl Into<ProviderHandle>) -> Self {
+            self.route_layer(from_fn_with_state(
+                Layered::new(provider.into(), None),
+                services_middleware,
+            ))
+        }
+
+        fn with_provider_stacking(self, provider: impl Into<ProviderHandle>, stacking: Stacking) -> Self {
+            self.route_layer(from_fn_with_state(
+                Layered::new(provider.into(), Some(stacking)),
+                services_middleware,
+            ))
+        }
+
+        fn with_tenant_providers(self, resolver: TenantResolver, providers: TenantProviders) -> Self {
+            self.route_layer(from_fn_with_state(Tenancy::new(resolver, providers), tenant_middleware))
+        }
+
+        fn with_child_services<F: FnOnce(&mut ServiceCollection)>(self, configure: F) -> Self {
+            self.try_with_child_services(configure)
+                .unwrap_or_else(|error| panic!("The child services are not valid. {}", error))
+        }
+
+        fn try_with_child_services<F: FnOnce(&mut ServiceCollection)>(
+            self,
+            configure: F,
+        ) -> Result<Self, ValidationError> {
+            Ok(self.route_layer(from_fn_with_state(child::build(configure)?, child_middleware)))
+        }
+
+        fn with_lock_timeout(self, timeout: Duration) -> Self {
+            self.route_layer(Extension(LockTimeout(timeout)))
+        }
+
+        fn with_service_extension<T: ?Sized + Any + Send + Sync>(self) -> Self {
+            self.route_layer(from_fn(extension_middleware::<T>))
+        }
+
+        fn layer_from_services<T: ?Sized + Middleware + 'static>(self) -> Self {
+            self.route_layer(from_fn_in_scope(middleware::invoke::<T>))
+        }
+
+        fn route_controller<C: ?Sized + Send + Sync + 'static>(
+            self,
+            path: &str,
+            configure: impl FnOnce(ControllerRoute<C, S>) -> ControllerRoute<C, S>,
+        ) -> Self {
+            self.route(path, configure(ControllerRoute::new()).into_router())
         }
     }
 }
@@ -0,0 +1,359 @@
+use crate::{
+    inject::missing_provider,
+    inject_lock::LockTracker,
+    scope::{self, Origin},
+    Inject, ProviderHandle,
+};
+use axum::{
+    extract::Request,
+    middleware::{from_fn, FromFn, Next},
+    response::{IntoResponse, Response},
+};
+use di::ServiceProvider;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{util::BoxCloneSyncService, Layer, Service, ServiceExt};
+
+type BoxService = BoxCloneSyncService<Request, Response, Infallible>;
+
+/// Represents the future returned by [middleware](Middleware).
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+type BoxLayer = Arc<dyn Fn(BoxService) -> BoxService + Send + Sync>;
+
+/// Defines the behavior of middleware that is resolved from the scope of a request.
+///
+/// # Remarks
+///
+/// Middleware is registered as a service with its own dependencies and added to a router with
+/// [`layer_from_services`](crate::prelude::RouterExt::layer_from_services). The middleware is resolved from the scope
+/// of each request, which means it has access to the same scoped services as the handler.
+pub trait Middleware: Send + Sync {
+    /// Invokes the middleware.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - the current [request](Request)
+    /// * `next` - the [remaining middleware stack](Next), including the handler
+    fn invoke(&self, request: Request, next: Next) -> BoxFuture<'_, Response>;
+}
+
+pub(crate) async fn invoke<T>(Inject(middleware): Inject<T>, request: Request, next: Next) -> Response
+where
+    T: ?Sized + Middleware + 'static,
+{
+    middleware.invoke(request, next).await
+}
+
+fn boxed<F, T>(f: F) -> BoxLayer
+where
+    F: Clone + Send + Sync + 'static,
+    T: 'static,
+    FromFn<F, (), BoxService, T>:
+        Service<Request, Response = Response, Error = Infallible> + Clone + Send + Sync + 'static,
+    <FromFn<F, (), BoxService, T> as Service<Request>>::Future: Send + 'static,
+{
+    Arc::new(move |inner| BoxService::new(from_fn::<F, T>(f.clone()).layer(inner)))
+}
+
+/// Creates a middleware from an async function that can inject services.
+///
+/// # Arguments
+///
+/// * `provider` - the [service provider](di::ServiceProvider) or [handle](ProviderHandle) the scope is created from
+/// * `f` - the async function used as middleware
+///
+/// # Remarks
+///
+/// The function has the same signature as a function used with [`from_fn`], which means it can have parameters such
+/// as [`Inject`] and [`InjectWithKey`](crate::InjectWithKey). The middleware shares the same scope as the handler
+/// regardless of the order in which it is added. When a scope has not been added to the request, the middleware
+/// creates the scope from the service provider. A service provider added with
+/// [`with_provider`](crate::prelude::RouterExt::with_provider) after the middleware reuses the scope when it is the
+/// same [handle](ProviderHandle); otherwise, the scope is replaced as if the middleware had not created it.
+pub fn from_fn_with_services<F, T>(provider: impl Into<ProviderHandle>, f: F) -> FromFnWithServicesLayer
+where
+    F: Clone + Send + Sync + 'static,
+    T: 'static,
+    FromFn<F, (), BoxService, T>:
+        Service<Request, Response = Response, Error = Infallible> + Clone + Send + Sync + 'static,
+    <FromFn<F, (), BoxService, T> as Service<Request>>::Future: Send + 'static,
+{
+    FromFnWithServicesLayer {
+        provider: Some(provider.into()),
+        layer: boxed(f),
+    }
+}
+
+/// Creates a middleware from an async function that can inject services from an existing scope.
+///
+/// # Arguments
+///
+/// * `f` - the async function used as middleware
+///
+/// # Remarks
+///
+/// The HTTP request short-circuits with HTTP status code 500 - Internal Server Error if a scope has not been added to
+/// the request when the middleware runs.
+pub(crate) fn from_fn_in_scope<F, T>(f: F) -> FromFnWithServicesLayer
+where
+    F: Clone + Send + Sync + 'static,
+    T: 'static,
+    FromFn<F, (), BoxService, T>:
+        Service<Request, Response = Response, Error = Infallible> + Clone + Send + Sync + 'static,
+    <FromFn<F, (), BoxService, T> as Service<Request>>::Future: Send + 'static,
+{
+    FromFnWithServicesLayer {
+        provider: None,
+        layer: boxed(f),
+    }
+}
+
+/// Represents a [layer](Layer) for middleware created with [`from_fn_with_services`].
+#[derive(Clone)]
+pub struct FromFnWithServicesLayer {
+    provider: Option<ProviderHandle>,
+    layer: BoxLayer,
+}
+
+impl<I> Layer<I> for FromFnWithServicesLayer {
+    type Service = FromFnWithServices<I>;
+
+    fn layer(&self, inner: I) -> Self::Service {
+        FromFnWithServices {
+            inner,
+            provider: self.provider.clone(),
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+/// Represents middleware created with [`from_fn_with_services`].
+#[derive(Clone)]
+pub struct FromFnWithServices<I> {
+    inner: I,
+    provider: Option<ProviderHandle>,
+    layer: BoxLayer,
+}
+
+impl<I> Service<Request> for FromFnWithServices<I>
+where
+    I: Service<Request, Response = Response, Error = Infallible> + Clone + Send + Sync + 'static,
+    I::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // the inner service is cloned and driven to readiness for each request
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        if request.extensions().get::<ServiceProvider>().is_none() {
+            if let Some(provider) = &self.provider {
+                scope::begin(&mut request, &provider.current());
+                request.extensions_mut().insert(Origin(provider.clone()));
+            } else {
+                // the middleware would otherwise be skipped or run without the scope of the handler
+                return Box::pin(async { Ok(missing_provider().into_response()) });
+            }
+        }
+
+        let inner = BoxService::new(self.inner.clone().map_request(|request: Request| {
+            // the mutable services of the middleware are not compared with those of the next middleware or handler
+            if let Some(tracker) = request.extensions().get::<LockTracker>() {
+                tracker.next_frame();
+            }
+
+            request
+        }));
+
+        Box::pin((self.layer)(inner).oneshot(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::{prelude::*, InjectMut};
+    use axum::http::StatusCode;
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, Injectable, RefMut, ServiceCollection};
+
+    #[injectable]
+    #[derive(Default)]
+    struct Counter {
+        value: usize,
+    }
+
+    async fn count(InjectMut(counter): InjectMut<Counter>, request: Request, next: Next) -> Response {
+        counter.write().unwrap().value += 1;
+        next.run(request).await
+    }
+
+    async fn handler(InjectMut(counter): InjectMut<Counter>) -> String {
+        counter.read().unwrap().value.to_string()
+    }
+
+    fn new_provider() -> ServiceProvider {
+        ServiceCollection::new()
+            .add(Counter::scoped().as_mut())
+            .build_provider()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn middleware_should_share_scope_with_handler() {
+        // arrange
+        let provider = ProviderHandle::new(new_provider());
+        let app = Router::new()
+            .route("/test", get(handler))
+            .route_layer(from_fn_with_services(provider.clone(), count))
+            .with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "1");
+    }
+
+    #[tokio::test]
+    async fn middleware_should_share_scope_with_handler_when_nested() {
+        // arrange
+        let provider = ProviderHandle::new(new_provider());
+        let app = Router::new()
+            .route("/test", get(handler))
+            .route_layer(from_fn_with_services(provider.clone(), count))
+            .route_layer(from_fn_with_services(provider.clone(), count))
+            .with_provider(provider);
+        let app = Router::new().nest("/api", app);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/api/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "2");
+    }
+
+    #[tokio::test]
+    async fn middleware_added_after_provider_should_share_scope_with_handler() {
+        // arrange
+        let provider = ProviderHandle::new(new_provider());
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_provider(provider.clone())
+            .layer(from_fn_with_services(provider, count));
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+        let missing = client.get("/missing").into_future().await;
+
+        // assert
+        assert_eq!(&text, "1");
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn middleware_should_create_scope_for_handler() {
+        // arrange
+        let app = Router::new()
+            .route("/test", get(handler))
+            .layer(from_fn_with_services(new_provider(), count));
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "1");
+    }
+
+    trait AuditMiddleware: Middleware {}
+
+    #[injectable]
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    #[injectable(AuditMiddleware)]
+    struct Auditor {
+        log: RefMut<Log>,
+    }
+
+    impl AuditMiddleware for Auditor {}
+
+    impl Middleware for Auditor {
+        fn invoke(&self, request: Request, next: Next) -> BoxFuture<'_, Response> {
+            Box::pin(async move {
+                self.log
+                    .write()
+                    .unwrap()
+                    .0
+                    .push(format!("audit {}", request.uri().path()));
+                next.run(request).await
+            })
+        }
+    }
+
+    async fn audited(InjectMut(log): InjectMut<Log>) -> String {
+        log.read().unwrap().0.join(",")
+    }
+
+    #[tokio::test]
+    async fn middleware_resolved_from_services_should_share_scope_with_handler() {
+        // arrange
+        let provider = ServiceCollection::new()
+            .add(Log::scoped().as_mut())
+            .add(Auditor::scoped())
+            .build_provider()
+            .unwrap();
+        let app = Router::new()
+            .route("/test", get(audited))
+            .layer_from_services::<dyn AuditMiddleware>()
+            .with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let first = client.get("/test").into_future().await.text().await;
+        let second = client.get("/test").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&first, "audit /test");
+        assert_eq!(&second, "audit /test");
+    }
+
+    #[tokio::test]
+    async fn request_should_fail_with_500_for_unregistered_middleware() {
+        // arrange
+        let app = Router::new()
+            .route("/test", get(audited))
+            .layer_from_services::<dyn AuditMiddleware>()
+            .with_provider(ServiceProvider::default());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
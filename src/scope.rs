@@ -0,0 +1,429 @@
+use crate::{scoped_value, ProviderHandle};
+use axum::{
+    extract::Request,
+    http::{request::Parts, Extensions},
+};
+use di::{singleton, KeyedRef, KeyedRefMut, Ref, RefMut, ServiceDescriptor, ServiceProvider};
+use std::any::Any;
+use std::vec::IntoIter;
+
+/// Represents the behavior of a service provider when a scope has already been added to a request.
+///
+/// # Remarks
+///
+/// A scope has already been added to a request when a router with a service provider is nested in or merged into
+/// another router with a service provider.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Stacking {
+    /// Indicates the existing scope is replaced by a new scope.
+    #[default]
+    Replace,
+
+    /// Indicates the existing scope is reused and a new scope is not created.
+    Reuse,
+
+    /// Indicates a new scope is created as a child of the existing scope.
+    ///
+    /// # Remarks
+    ///
+    /// A service is resolved from the new scope first and then from the existing scope.
+    Child,
+}
+
+/// Represents the collision of service providers that replaced the scope of a request.
+///
+/// # Remarks
+///
+/// A collision is recorded in the request extensions when a service provider added with
+/// [`with_provider`](crate::prelude::RouterExt::with_provider) replaces a scope that has already been added to the
+/// request, which usually means that routers from different sources were composed without specifying the
+/// [stacking](Stacking) behavior. A collision is not recorded when the behavior is specified with
+/// [`with_provider_stacking`](crate::prelude::RouterExt::with_provider_stacking). Collisions are only recorded in
+/// debug builds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScopeCollision {
+    replaced: usize,
+}
+
+impl ScopeCollision {
+    /// Gets the number of scopes that have been replaced.
+    pub fn replaced(&self) -> usize {
+        self.replaced
+    }
+}
+
+/// Records a collision when the existing scope of a request is replaced without a specified stacking behavior.
+///
+/// # Arguments
+///
+/// * `request` - the [request](Request) whose scope is replaced
+#[cfg(debug_assertions)]
+pub(crate) fn collide(request: &mut Request) {
+    let extensions = request.extensions_mut();
+
+    if extensions.get::<ServiceProvider>().is_some() {
+        let mut collision = extensions.remove::<ScopeCollision>().unwrap_or_default();
+        collision.replaced += 1;
+        extensions.insert(collision);
+    }
+}
+
+/// Represents the parent scopes of a request scope, from the nearest to the farthest.
+#[derive(Clone, Default)]
+pub(crate) struct Ancestors(pub Vec<ServiceProvider>);
+
+/// Represents the service provider a scope was created from by a middleware.
+///
+/// # Remarks
+///
+/// A service provider added after the middleware reuses the scope when it is the same handle.
+#[derive(Clone)]
+pub(crate) struct Origin(pub ProviderHandle);
+
+/// Represents a function that initializes a new scope for a request.
+pub(crate) struct Initializer(fn(&ServiceProvider, &Request));
+
+/// Creates a descriptor for a function that initializes a new scope for a request.
+///
+/// # Arguments
+///
+/// * `initialize` - the function that initializes the scope
+///
+/// # Remarks
+///
+/// The implementation type distinguishes the initializer so that it can be added to all initializers only once.
+pub(crate) fn initializer<TImpl: 'static>(initialize: fn(&ServiceProvider, &Request)) -> ServiceDescriptor {
+    singleton::<Initializer, TImpl>().from(move |_| Ref::new(Initializer(initialize)))
+}
+
+/// Creates a new scope for a request.
+///
+/// # Arguments
+///
+/// * `request` - the [request](Request) the scope is created for
+/// * `provider` - the [service provider](ServiceProvider) the scope is created from
+pub(crate) fn create(request: &Request, provider: &ServiceProvider) -> ServiceProvider {
+    let scope = provider.create_scope();
+
+    for initializer in scope.get_all::<Initializer>() {
+        (initializer.0)(&scope, request);
+    }
+
+    scope
+}
+
+/// Begins a new scope for a request.
+///
+/// # Arguments
+///
+/// * `request` - the [request](Request) the scope is added to
+/// * `provider` - the [service provider](ServiceProvider) the scope is created from
+///
+/// # Remarks
+///
+/// The new scope replaces the existing scope of the request, if any, including its parent scopes.
+pub(crate) fn begin(request: &mut Request, provider: &ServiceProvider) {
+    let scope = create(request, provider);
+    let extensions = request.extensions_mut();
+
+    extensions.remove::<Ancestors>();
+    extensions.remove::<Origin>();
+    extensions.insert(scope);
+
+    #[cfg(debug_assertions)]
+    request
+        .extensions_mut()
+        .insert(crate::inject_lock::LockTracker::default());
+}
+
+/// Determines whether the scope of a request was created from the specified service provider by a middleware.
+///
+/// # Arguments
+///
+/// * `request` - the current [request](Request)
+/// * `provider` - the [handle](ProviderHandle) of the service provider
+pub(crate) fn is_origin(request: &Request, provider: &ProviderHandle) -> bool {
+    request
+        .extensions()
+        .get::<Origin>()
+        .is_some_and(|origin| origin.0.ptr_eq(provider))
+}
+
+/// Adds a scope to a request that might already have a scope.
+///
+/// # Arguments
+///
+/// * `request` - the [request](Request) the scope is added to
+/// * `provider` - the [service provider](ServiceProvider) the scope is created from
+/// * `stacking` - the [stacking](Stacking) behavior used when the request already has a scope
+pub(crate) fn stack(request: &mut Request, provider: &ServiceProvider, stacking: Stacking) {
+    if request.extensions().get::<ServiceProvider>().is_none() {
+        begin(request, provider);
+        return;
+    }
+
+    match stacking {
+        Stacking::Replace => {
+            let scope = create(request, provider);
+            let extensions = request.extensions_mut();
+            extensions.remove::<Ancestors>();
+            extensions.remove::<Origin>();
+            extensions.insert(scope);
+        }
+        Stacking::Reuse => {}
+        Stacking::Child => crate::child::enter(request, provider),
+    }
+}
+
+/// Represents the services available to a request.
+///
+/// # Remarks
+///
+/// A service is resolved from the request scope first and then from each of its parent scopes, if any. A collection
+/// of services contains the services of the farthest parent scope first and the services of the request scope last,
+/// which is the same order as if all of the services were registered in a single collection.
+pub(crate) struct Services<'a> {
+    scope: &'a ServiceProvider,
+    ancestors: &'a [ServiceProvider],
+}
+
+/// Gets the services available to a request, if any.
+///
+/// # Arguments
+///
+/// * `parts` - the [parts](Parts) of the current request
+pub(crate) fn services(parts: &Parts) -> Option<Services<'_>> {
+    from_extensions(&parts.extensions)
+}
+
+/// Gets the services available to a request from its extensions, if any.
+///
+/// # Arguments
+///
+/// * `extensions` - the [extensions](Extensions) of the current request
+pub(crate) fn from_extensions(extensions: &Extensions) -> Option<Services<'_>> {
+    Some(Services {
+        scope: extensions.get::<ServiceProvider>()?,
+        ancestors: extensions
+            .get::<Ancestors>()
+            .map(|ancestors| ancestors.0.as_slice())
+            .unwrap_or_default(),
+    })
+}
+
+impl<'a> Services<'a> {
+    pub(crate) fn first<R>(&self, resolve: impl Fn(&'a ServiceProvider) -> Option<R>) -> Option<R> {
+        // a service that depends on a scoped value that has not been set cannot be resolved
+        std::iter::once(self.scope)
+            .chain(self.ancestors)
+            .find_map(|provider| scoped_value::resolve(|| resolve(provider)).ok().flatten())
+    }
+
+    pub(crate) fn all<R, I>(&self, resolve: impl Fn(&'a ServiceProvider) -> I) -> IntoIter<R>
+    where
+        I: IntoIterator<Item = R>,
+    {
+        self.ancestors
+            .iter()
+            .rev()
+            .chain(std::iter::once(self.scope))
+            .flat_map(|provider| {
+                scoped_value::resolve(|| resolve(provider).into_iter().collect::<Vec<_>>()).unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    pub(crate) fn get<T: Any + ?Sized>(&self) -> Option<Ref<T>> {
+        self.try_get().ok().flatten()
+    }
+
+    /// Gets a service unless it depends on a scoped value that has not been set.
+    ///
+    /// # Remarks
+    ///
+    /// The name of the type of the first scoped value that has not been set is returned when the service cannot be
+    /// resolved from any scope.
+    pub(crate) fn try_get<T: Any + ?Sized>(&self) -> Result<Option<Ref<T>>, &'static str> {
+        let mut unset = None;
+
+        for provider in std::iter::once(self.scope).chain(self.ancestors) {
+            match scoped_value::try_get::<T>(provider) {
+                Ok(None) => {}
+                Err(name) => {
+                    unset.get_or_insert(name);
+                }
+                service => return service,
+            }
+        }
+
+        unset.map_or(Ok(None), Err)
+    }
+
+    pub(crate) fn get_mut<T: Any + ?Sized>(&self) -> Option<RefMut<T>> {
+        self.first(ServiceProvider::get_mut::<T>)
+    }
+
+    pub(crate) fn get_by_key<TKey, TSvc: Any + ?Sized>(&self) -> Option<KeyedRef<TKey, TSvc>> {
+        self.first(ServiceProvider::get_by_key::<TKey, TSvc>)
+    }
+
+    pub(crate) fn get_by_key_mut<TKey, TSvc: Any + ?Sized>(&self) -> Option<KeyedRefMut<TKey, TSvc>> {
+        self.first(ServiceProvider::get_by_key_mut::<TKey, TSvc>)
+    }
+
+    pub(crate) fn get_all<T: Any + ?Sized>(&self) -> IntoIter<Ref<T>> {
+        self.all(ServiceProvider::get_all::<T>)
+    }
+
+    pub(crate) fn get_all_mut<T: Any + ?Sized>(&self) -> IntoIter<RefMut<T>> {
+        self.all(ServiceProvider::get_all_mut::<T>)
+    }
+
+    pub(crate) fn get_all_by_key<TKey: 'a, TSvc: Any + ?Sized>(&self) -> IntoIter<KeyedRef<TKey, TSvc>> {
+        self.all(ServiceProvider::get_all_by_key::<TKey, TSvc>)
+    }
+
+    pub(crate) fn get_all_by_key_mut<TKey: 'a, TSvc: Any + ?Sized>(&self) -> IntoIter<KeyedRefMut<TKey, TSvc>> {
+        self.all(ServiceProvider::get_all_by_key_mut::<TKey, TSvc>)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::{prelude::*, Inject};
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{existing_as_self, ServiceCollection};
+
+    struct Name(&'static str);
+
+    struct Greeting(&'static str);
+
+    fn new_provider(name: &'static str) -> ServiceProvider {
+        ServiceCollection::new()
+            .add(existing_as_self(Name(name)))
+            .build_provider()
+            .unwrap()
+    }
+
+    async fn handler(Inject(name): Inject<Name>) -> String {
+        name.0.into()
+    }
+
+    fn new_app(inner: Router) -> Router {
+        Router::new().nest("/inner", inner).with_provider(new_provider("Outer"))
+    }
+
+    #[tokio::test]
+    async fn stacked_provider_should_replace_existing_scope() {
+        // arrange
+        let inner = Router::new()
+            .route("/test", get(handler))
+            .with_provider_stacking(new_provider("Inner"), Stacking::Replace);
+
+        let client = TestClient::new(new_app(inner));
+
+        // act
+        let response = client.get("/inner/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Inner");
+    }
+
+    #[tokio::test]
+    async fn stacked_provider_should_reuse_existing_scope() {
+        // arrange
+        let inner = Router::new()
+            .route("/test", get(handler))
+            .with_provider_stacking(new_provider("Inner"), Stacking::Reuse);
+
+        let client = TestClient::new(new_app(inner));
+
+        // act
+        let response = client.get("/inner/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Outer");
+    }
+
+    #[tokio::test]
+    async fn stacked_provider_should_create_child_of_existing_scope() {
+        // arrange
+        async fn handler(Inject(greeting): Inject<Greeting>, Inject(name): Inject<Name>) -> String {
+            format!("{}, {}", greeting.0, name.0)
+        }
+
+        let provider = ServiceCollection::new()
+            .add(existing_as_self(Greeting("Hello")))
+            .build_provider()
+            .unwrap();
+        let inner = Router::new()
+            .route("/test", get(handler))
+            .with_provider_stacking(provider, Stacking::Child);
+
+        let client = TestClient::new(new_app(inner));
+
+        // act
+        let response = client.get("/inner/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Hello, Outer");
+    }
+
+    #[tokio::test]
+    async fn stacked_provider_should_create_new_scope_without_existing_scope() {
+        // arrange
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_provider_stacking(new_provider("Inner"), Stacking::Reuse);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Inner");
+    }
+
+    #[tokio::test]
+    #[cfg(debug_assertions)]
+    async fn nested_provider_should_record_collision_with_existing_scope() {
+        // arrange
+        use axum::Extension;
+
+        async fn handler(collision: Option<Extension<ScopeCollision>>) -> String {
+            collision
+                .map_or(0, |Extension(collision)| collision.replaced())
+                .to_string()
+        }
+
+        let replaced = Router::new()
+            .route("/test", get(handler))
+            .with_provider(new_provider("Inner"));
+        let stacked = Router::new()
+            .route("/test", get(handler))
+            .with_provider_stacking(new_provider("Inner"), Stacking::Replace);
+        let app = Router::new()
+            .nest("/replaced", replaced)
+            .nest("/stacked", stacked)
+            .with_provider(new_provider("Outer"));
+
+        let client = TestClient::new(app);
+
+        // act
+        let replaced = client.get("/replaced/test").into_future().await.text().await;
+        let stacked = client.get("/stacked/test").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&replaced, "1");
+        assert_eq!(&stacked, "0");
+    }
+}
@@ -0,0 +1,428 @@
+use crate::scope;
+use axum::http::Request;
+use di::{scoped, scoped_factory, Ref, ServiceDescriptor, ServiceProvider, Type};
+use std::any::{type_name, Any};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::OnceLock;
+
+/// Represents a scoped value that is set after the scope for a request has been created.
+///
+/// # Remarks
+///
+/// A scoped value is typically set by a middleware using [`RequestExt::services_mut`]. When the value has not been set,
+/// [`TryInject<T>`](crate::TryInject) is `None` and [`Inject<T>`](crate::Inject) short-circuits the request with HTTP
+/// status code 500 - Internal Server Error. A service that depends on `Ref<T>`, directly or transitively, requires the
+/// value to be set before the service is resolved. The extractors treat such a service the same as the value itself,
+/// but resolving it directly from a service provider panics. A service that depends on `Ref<ScopedValue<T>>` can
+/// determine whether the value has been set, which is required when the value is optional.
+pub struct ScopedValue<T: ?Sized>(OnceLock<Ref<T>>);
+
+impl<T: ?Sized> ScopedValue<T> {
+    pub(crate) fn set(&self, value: Ref<T>) -> Result<(), Ref<T>> {
+        self.0.set(value)
+    }
+
+    /// Gets the scoped value, if it has been set.
+    pub fn get(&self) -> Option<Ref<T>> {
+        self.0.get().cloned()
+    }
+}
+
+impl<T: ?Sized> Default for ScopedValue<T> {
+    fn default() -> Self {
+        Self(OnceLock::new())
+    }
+}
+
+/// Represents the implementation type of a service that is resolved from a scoped value.
+struct FromScopedValue;
+
+/// Determines whether the specified descriptor resolves a service from a scoped value.
+///
+/// # Remarks
+///
+/// A service resolved from a scoped value can only be resolved after the value has been set for a request.
+pub(crate) fn is_scoped_value(descriptor: &ServiceDescriptor) -> bool {
+    *descriptor.implementation_type() == Type::of::<FromScopedValue>()
+}
+
+thread_local! {
+    // the type of the scoped value whose descriptor is panicking on the current thread, if any
+    static UNSET: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Resolves a service unless a scoped value that has not been set is resolved.
+///
+/// # Arguments
+///
+/// * `resolve` - the function that resolves the service
+///
+/// # Remarks
+///
+/// The descriptor of a scoped value panics when the value has not been set. The panic is caught and the name of the
+/// type of the value is returned instead, regardless of how deep the value is in the dependencies of the service. Any
+/// other panic is resumed. The panic is still reported by the panic hook and aborts the process when the profile uses
+/// `panic = "abort"`.
+pub(crate) fn resolve<R>(resolve: impl FnOnce() -> R) -> Result<R, &'static str> {
+    UNSET.set(None);
+
+    match catch_unwind(AssertUnwindSafe(resolve)) {
+        Ok(result) => Ok(result),
+        Err(payload) => match UNSET.take() {
+            Some(name) => Err(name),
+            None => resume_unwind(payload),
+        },
+    }
+}
+
+/// Gets a service from a service provider unless it depends on a scoped value that has not been set.
+///
+/// # Arguments
+///
+/// * `provider` - the [service provider](ServiceProvider) to resolve the service from
+///
+/// # Remarks
+///
+/// The name of the type of the scoped value is returned when the service is, or depends on, a scoped value that has
+/// not been set.
+pub(crate) fn try_get<T: Any + ?Sized>(provider: &ServiceProvider) -> Result<Option<Ref<T>>, &'static str> {
+    if let Some(value) = provider.get::<ScopedValue<T>>() {
+        // the value itself is checked without unwinding
+        if value.0.get().is_none() {
+            return Err(type_name::<T>());
+        }
+    }
+
+    resolve(|| provider.get::<T>())
+}
+
+/// Creates the descriptors for a scoped value.
+///
+/// # Remarks
+///
+/// The first descriptor is the scoped value itself. The second descriptor resolves the value from the scoped value so
+/// that the value can be a dependency of other services.
+pub(crate) fn descriptors<T: Any + Send + Sync>() -> [ServiceDescriptor; 2] {
+    [
+        scoped_factory(|_| Ref::new(ScopedValue::<T>::default())),
+        scoped::<T, FromScopedValue>().from(|sp| {
+            sp.get_required::<ScopedValue<T>>().get().unwrap_or_else(|| {
+                UNSET.set(Some(type_name::<T>()));
+                panic!(
+                    "The scoped value for type '{}' has not been set in the current scope.",
+                    type_name::<T>()
+                )
+            })
+        }),
+    ]
+}
+
+/// Represents the implementation type of a bridged request extension.
+pub(crate) struct Bridged<T>(PhantomData<T>);
+
+/// Sets a scoped value from the request extension of the same type, if any.
+///
+/// # Arguments
+///
+/// * `scope` - the new scope
+/// * `request` - the [request](axum::extract::Request) the scope was created for
+pub(crate) fn bridge<T: Clone + Any + Send + Sync>(scope: &ServiceProvider, request: &axum::extract::Request) {
+    if let Some(value) = request.extensions().get::<T>() {
+        if let Some(scoped) = scope.get::<ScopedValue<T>>() {
+            let _ = scoped.set(Ref::new(value.clone()));
+        }
+    }
+}
+
+/// Represents the scoped values of a request.
+pub struct ScopedValues<'a>(scope::Services<'a>);
+
+impl ScopedValues<'_> {
+    /// Sets a scoped value in the scope of the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - the value to set
+    ///
+    /// # Remarks
+    ///
+    /// The value is returned if the scoped value has not been added with
+    /// [`add_scoped_value`](crate::prelude::ServiceCollectionExt::add_scoped_value) or it has already been set.
+    pub fn insert<T: Any + Send + Sync>(&self, value: T) -> Result<(), T> {
+        let Some(scoped) = self.0.get::<ScopedValue<T>>() else {
+            return Err(value);
+        };
+
+        if scoped.get().is_some() {
+            return Err(value);
+        }
+
+        scoped
+            .set(Ref::new(value))
+            .map_err(|value| Ref::into_inner(value).expect("the value should not be shared"))
+    }
+}
+
+/// Provides [request](Request) extension methods.
+pub trait RequestExt {
+    /// Gets the scoped values of the request, if a scope has been added.
+    fn services_mut(&self) -> Option<ScopedValues<'_>>;
+}
+
+impl<B> RequestExt for Request<B> {
+    fn services_mut(&self) -> Option<ScopedValues<'_>> {
+        scope::from_extensions(self.extensions()).map(ScopedValues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+
+    use super::*;
+    use crate::{prelude::*, Inject, TryInject};
+    use axum::{
+        extract::Request,
+        http::StatusCode,
+        middleware::{from_fn, Next},
+        response::Response,
+        routing::get,
+        test_helpers::TestClient,
+        Extension, Router,
+    };
+    use di::{injectable, Injectable, ServiceCollection, ServiceProvider};
+
+    struct CurrentUser {
+        name: String,
+    }
+
+    #[injectable]
+    struct Greeter {
+        user: Ref<CurrentUser>,
+    }
+
+    #[injectable]
+    struct OptionalGreeter {
+        user: Ref<ScopedValue<CurrentUser>>,
+    }
+
+    async fn authenticate(request: Request, next: Next) -> Response {
+        if let Some(name) = request.headers().get("x-user").and_then(|value| value.to_str().ok()) {
+            let user = CurrentUser { name: name.into() };
+            let _ = request.services_mut().unwrap().insert(user);
+        }
+
+        next.run(request).await
+    }
+
+    fn new_provider() -> ServiceProvider {
+        ServiceCollection::new()
+            .add(Greeter::scoped())
+            .add(OptionalGreeter::scoped())
+            .add_scoped_value::<CurrentUser>()
+            .build_provider()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn scoped_value_set_by_middleware_should_be_injected_into_service() {
+        // arrange
+        async fn handler(Inject(greeter): Inject<Greeter>) -> String {
+            format!("Hello, {}", greeter.user.name)
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .route_layer(from_fn(authenticate))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").header("x-user", "Bob").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Hello, Bob");
+    }
+
+    #[tokio::test]
+    async fn optional_scoped_value_should_be_injected_into_service() {
+        // arrange
+        async fn handler(Inject(greeter): Inject<OptionalGreeter>) -> String {
+            match greeter.user.get() {
+                Some(user) => format!("Hello, {}", user.name),
+                None => "Hello, stranger".into(),
+            }
+        }
+
+        let app = Router::new()
+            .route("/test", get(handler))
+            .route_layer(from_fn(authenticate))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let bob = client
+            .get("/test")
+            .header("x-user", "Bob")
+            .into_future()
+            .await
+            .text()
+            .await;
+        let stranger = client.get("/test").into_future().await.text().await;
+
+        // assert
+        assert_eq!(&bob, "Hello, Bob");
+        assert_eq!(&stranger, "Hello, stranger");
+    }
+
+    #[tokio::test]
+    async fn unset_scoped_value_should_not_be_injected() {
+        // arrange
+        async fn optional(TryInject(user): TryInject<CurrentUser>) -> String {
+            user.map(|user| user.name.clone()).unwrap_or("none".into())
+        }
+
+        async fn required(Inject(user): Inject<CurrentUser>) -> String {
+            user.name.clone()
+        }
+
+        let app = Router::new()
+            .route("/optional", get(optional))
+            .route("/required", get(required))
+            .route_layer(from_fn(authenticate))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let optional = client.get("/optional").into_future().await.text().await;
+        let required = client.get("/required").into_future().await;
+
+        // assert
+        assert_eq!(&optional, "none");
+        assert_eq!(required.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(required.text().await.contains("has not been set"));
+    }
+
+    #[tokio::test]
+    async fn service_depending_on_unset_scoped_value_should_not_be_injected() {
+        // arrange
+        async fn optional(TryInject(greeter): TryInject<Greeter>) -> String {
+            greeter
+                .map(|greeter| greeter.user.name.clone())
+                .unwrap_or("none".into())
+        }
+
+        async fn required(Inject(greeter): Inject<Greeter>) -> String {
+            format!("Hello, {}", greeter.user.name)
+        }
+
+        let app = Router::new()
+            .route("/optional", get(optional))
+            .route("/required", get(required))
+            .route_layer(from_fn(authenticate))
+            .with_provider(new_provider());
+
+        let client = TestClient::new(app);
+
+        // act
+        let optional = client.get("/optional").into_future().await.text().await;
+        let required = client.get("/required").into_future().await;
+
+        // assert
+        assert_eq!(&optional, "none");
+        assert_eq!(required.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(required
+            .text()
+            .await
+            .contains("The scoped value for type 'di_axum::scoped_value::tests::CurrentUser' has not been set."));
+    }
+
+    #[test]
+    fn insert_should_return_value_when_scoped_value_cannot_be_set() {
+        // arrange
+        let provider = ServiceCollection::new()
+            .add_scoped_value::<CurrentUser>()
+            .build_provider()
+            .unwrap();
+        let mut request = Request::new(());
+
+        request.extensions_mut().insert(provider.create_scope());
+
+        let services = request.services_mut().unwrap();
+        let first = services.insert(CurrentUser { name: "Bob".into() });
+
+        // act
+        let second = services.insert(CurrentUser { name: "Alice".into() });
+        let unregistered = services.insert(42_u8);
+
+        // assert
+        assert!(first.is_ok());
+        assert_eq!(second.err().unwrap().name, "Alice");
+        assert_eq!(unregistered.err(), Some(42));
+    }
+
+    #[derive(Clone)]
+    struct RequestId(&'static str);
+
+    #[injectable]
+    struct Auditor {
+        id: Ref<ScopedValue<RequestId>>,
+    }
+
+    #[tokio::test]
+    async fn bridged_extension_should_be_injected_into_service() {
+        // arrange
+        async fn handler(Inject(auditor): Inject<Auditor>) -> String {
+            auditor.id.get().map(|id| id.0).unwrap_or("none").into()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(Auditor::scoped())
+            .bridge_extension::<RequestId>()
+            .build_provider()
+            .unwrap();
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_provider(provider)
+            .layer(Extension(RequestId("42")));
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "42");
+    }
+
+    #[tokio::test]
+    async fn bridged_extension_should_not_be_set_when_missing() {
+        // arrange
+        async fn handler(Inject(auditor): Inject<Auditor>) -> String {
+            auditor.id.get().map(|id| id.0).unwrap_or("none").into()
+        }
+
+        let provider = ServiceCollection::new()
+            .add(Auditor::scoped())
+            .bridge_extension::<RequestId>()
+            .build_provider()
+            .unwrap();
+        let app = Router::new().route("/test", get(handler)).with_provider(provider);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "none");
+    }
+}
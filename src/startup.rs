@@ -0,0 +1,152 @@
+use crate::{middleware::BoxFuture, HostError};
+use di::ServiceProvider;
+use std::any::type_name;
+use std::error::Error;
+
+/// Defines the behavior of a task that runs before a [web host](crate::WebHost) starts serving requests.
+///
+/// # Remarks
+///
+/// Startup tasks are registered with [`try_add_to_all`](di::ServiceCollection::try_add_to_all) and are run one at a
+/// time in the order they were registered. Startup fails if any task fails, in which case the remaining tasks are not
+/// run.
+pub trait StartupTask: Send + Sync {
+    /// Gets the name of the task.
+    ///
+    /// # Remarks
+    ///
+    /// The name is used to identify the task when it fails. The default name is the type name of the task.
+    fn name(&self) -> &str {
+        type_name::<Self>()
+    }
+
+    /// Runs the startup task.
+    fn run(&self) -> BoxFuture<'_, Result<(), Box<dyn Error + Send + Sync>>>;
+}
+
+/// Runs all registered startup tasks in order.
+///
+/// # Arguments
+///
+/// * `provider` - the root [service provider](ServiceProvider)
+pub(crate) async fn run(provider: &ServiceProvider) -> Result<(), HostError> {
+    let scope = provider.create_scope();
+    let tasks: Vec<_> = scope.get_all::<dyn StartupTask>().collect();
+
+    for task in tasks {
+        if let Err(error) = task.run().await {
+            return Err(HostError::Startup(task.name().to_owned(), error));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WebHost;
+    use di::{injectable, singleton_factory, Injectable, Ref};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<&'static str>>);
+
+    impl Log {
+        fn write(&self, entry: &'static str) {
+            self.0.lock().unwrap().push(entry)
+        }
+
+        fn entries(&self) -> Vec<&'static str> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    #[injectable(StartupTask)]
+    struct Migrate {
+        log: Ref<Log>,
+    }
+
+    impl StartupTask for Migrate {
+        fn run(&self) -> BoxFuture<'_, Result<(), Box<dyn Error + Send + Sync>>> {
+            Box::pin(async move {
+                self.log.write("migrate");
+                Ok(())
+            })
+        }
+    }
+
+    #[injectable(StartupTask)]
+    struct WarmCache {
+        log: Ref<Log>,
+    }
+
+    impl StartupTask for WarmCache {
+        fn run(&self) -> BoxFuture<'_, Result<(), Box<dyn Error + Send + Sync>>> {
+            Box::pin(async move {
+                self.log.write("warm");
+                Ok(())
+            })
+        }
+    }
+
+    #[injectable(StartupTask)]
+    struct FetchKeys;
+
+    impl StartupTask for FetchKeys {
+        fn name(&self) -> &str {
+            "fetch keys"
+        }
+
+        fn run(&self) -> BoxFuture<'_, Result<(), Box<dyn Error + Send + Sync>>> {
+            Box::pin(async { Err("the vault is sealed".into()) })
+        }
+    }
+
+    fn new_host(log: Ref<Log>, fail: bool) -> WebHost {
+        WebHost::default()
+            .services(move |services| {
+                services
+                    .add(singleton_factory(move |_| log.clone()))
+                    .try_add_to_all(Migrate::transient());
+
+                if fail {
+                    services.try_add_to_all(FetchKeys::transient());
+                }
+
+                services.try_add_to_all(WarmCache::transient());
+            })
+            .bind("127.0.0.1:0")
+            .with_shutdown_signal(async {})
+    }
+
+    #[tokio::test]
+    async fn host_should_run_startup_tasks_in_order() {
+        // arrange
+        let log = Ref::new(Log::default());
+        let host = new_host(log.clone(), false);
+
+        // act
+        host.run().await.unwrap();
+
+        // assert
+        assert_eq!(log.entries(), vec!["migrate", "warm"]);
+    }
+
+    #[tokio::test]
+    async fn host_should_fail_when_startup_task_fails() {
+        // arrange
+        let log = Ref::new(Log::default());
+        let host = new_host(log.clone(), true);
+
+        // act
+        let error = host.run().await.unwrap_err();
+
+        // assert
+        assert_eq!(
+            error.to_string(),
+            "The startup task 'fetch keys' failed. the vault is sealed"
+        );
+        assert_eq!(log.entries(), vec!["migrate"]);
+    }
+}
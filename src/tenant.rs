@@ -0,0 +1,537 @@
+use crate::scope;
+use axum::{
+    extract::{Request, State},
+    http::{header::HOST, request::Parts, uri::Authority, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use di::ServiceProvider;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::OnceCell;
+
+/// Represents the strategy used to resolve the tenant of a request.
+#[derive(Clone, Copy, Debug)]
+pub enum TenantResolver {
+    /// Indicates the tenant is the host of the request, excluding the port.
+    Host,
+
+    /// Indicates the tenant is the value of the header with the specified name.
+    Header(&'static str),
+
+    /// Indicates the tenant is the first segment of the request path.
+    PathPrefix,
+
+    /// Indicates the tenant is resolved by the specified function.
+    ///
+    /// # Remarks
+    ///
+    /// A custom function can resolve the tenant from any other part of the request, such as a claim in a JSON Web
+    /// Token (JWT) that has been validated by an upstream middleware and added to the request extensions.
+    Custom(fn(&Parts) -> Option<String>),
+}
+
+impl TenantResolver {
+    fn resolve(&self, parts: &Parts) -> Option<String> {
+        match self {
+            Self::Host => parts
+                .headers
+                .get(HOST)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<Authority>().ok())
+                .map(|authority| authority.host().to_owned())
+                .or_else(|| parts.uri.host().map(ToOwned::to_owned)),
+            Self::Header(name) => parts
+                .headers
+                .get(*name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned),
+            Self::PathPrefix => parts
+                .uri
+                .path()
+                .trim_start_matches('/')
+                .split('/')
+                .next()
+                .filter(|segment| !segment.is_empty())
+                .map(ToOwned::to_owned),
+            Self::Custom(resolve) => resolve(parts),
+        }
+    }
+}
+
+type Factory = dyn Fn(&str) -> Option<ServiceProvider> + Send + Sync;
+type Rejection = dyn Fn(Option<&str>) -> Response + Send + Sync;
+
+fn reject(tenant: Option<&str>) -> Response {
+    if let Some(tenant) = tenant {
+        (
+            StatusCode::NOT_FOUND,
+            format!("The tenant '{}' is not recognized.", tenant),
+        )
+            .into_response()
+    } else {
+        (
+            StatusCode::BAD_REQUEST,
+            "The tenant could not be resolved from the request.",
+        )
+            .into_response()
+    }
+}
+
+type Slot = Arc<OnceCell<Option<ServiceProvider>>>;
+
+#[derive(Default)]
+struct Created {
+    slots: HashMap<String, Slot>,
+    order: VecDeque<String>,
+}
+
+impl Created {
+    fn slot(&mut self, tenant: &str, capacity: usize) -> Slot {
+        if let Some(slot) = self.slots.get(tenant) {
+            return slot.clone();
+        }
+
+        if capacity == 0 {
+            return Slot::default();
+        }
+
+        if self.order.len() >= capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.slots.remove(&oldest);
+            }
+        }
+
+        let slot = Slot::default();
+
+        self.order.push_back(tenant.to_owned());
+        self.slots.insert(tenant.to_owned(), slot.clone());
+        slot
+    }
+}
+
+/// Represents the service providers for a set of tenants.
+pub struct TenantProviders {
+    providers: HashMap<String, ServiceProvider>,
+    created: Mutex<Created>,
+    capacity: usize,
+    factory: Option<Box<Factory>>,
+    rejection: Box<Rejection>,
+}
+
+impl TenantProviders {
+    /// Initializes a new, empty set of tenant service providers.
+    pub fn new() -> Self {
+        Self {
+            providers: Default::default(),
+            created: Default::default(),
+            capacity: 1024,
+            factory: None,
+            rejection: Box::new(reject),
+        }
+    }
+
+    /// Adds the service provider for a tenant.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant` - the tenant the service provider is for
+    /// * `provider` - the [service provider](ServiceProvider) of the tenant
+    pub fn insert(mut self, tenant: impl Into<String>, provider: ServiceProvider) -> Self {
+        self.providers.insert(tenant.into(), provider);
+        self
+    }
+
+    /// Sets the factory used to create the service provider for a tenant that has not been added.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory` - the function that creates the [service provider](ServiceProvider) for a tenant, if any
+    ///
+    /// # Remarks
+    ///
+    /// The factory is invoked at most once for each tenant while the tenant is cached. The result, including a tenant
+    /// without a service provider, is retained and used for all subsequent requests of the tenant. Only requests for the
+    /// same tenant wait while the factory runs. The cache holds the results of the factory for the most recently created
+    /// tenants up to its [capacity](TenantProviders::with_capacity); the oldest result is evicted to make room for a new
+    /// tenant, after which the factory is invoked again the next time the evicted tenant is requested.
+    pub fn with_factory<F>(mut self, factory: F) -> Self
+    where
+        F: Fn(&str) -> Option<ServiceProvider> + Send + Sync + 'static,
+    {
+        self.factory = Some(Box::new(factory));
+        self
+    }
+
+    /// Sets the maximum number of tenants whose factory results are cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - the maximum number of cached tenants
+    ///
+    /// # Remarks
+    ///
+    /// The default capacity is 1024 tenants. The capacity bounds the memory used by tenants that are created by the
+    /// [factory](TenantProviders::with_factory), including unknown tenants, which could otherwise grow without limit
+    /// when the tenant is resolved from untrusted input. Service providers that have been added are never evicted. A
+    /// capacity of zero disables caching.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the function used to create the response for a request whose tenant is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `rejection` - the function that creates the [response](Response) for the tenant, if it was resolved
+    ///
+    /// # Remarks
+    ///
+    /// By default, the HTTP request short-circuits with HTTP status code 400 - Bad Request when the tenant cannot be
+    /// resolved and HTTP status code 404 - Not Found when the tenant has no service provider.
+    pub fn with_rejection<F>(mut self, rejection: F) -> Self
+    where
+        F: Fn(Option<&str>) -> Response + Send + Sync + 'static,
+    {
+        self.rejection = Box::new(rejection);
+        self
+    }
+
+    async fn get(&self, tenant: &str) -> Option<ServiceProvider> {
+        if let Some(provider) = self.providers.get(tenant) {
+            return Some(provider.clone());
+        }
+
+        let factory = self.factory.as_ref()?;
+        let slot = self
+            .created
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .slot(tenant, self.capacity);
+
+        // the factory runs outside of the lock so that only requests for the same tenant wait for it without blocking
+        // the runtime
+        slot.get_or_init(|| async { factory(tenant) }).await.clone()
+    }
+}
+
+impl Default for TenantProviders {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<HashMap<String, ServiceProvider>> for TenantProviders {
+    fn from(providers: HashMap<String, ServiceProvider>) -> Self {
+        Self {
+            providers,
+            ..Self::new()
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Tenancy {
+    resolver: TenantResolver,
+    providers: Arc<TenantProviders>,
+}
+
+impl Tenancy {
+    pub(crate) fn new(resolver: TenantResolver, providers: TenantProviders) -> Self {
+        Self {
+            resolver,
+            providers: Arc::new(providers),
+        }
+    }
+}
+
+pub(crate) async fn tenant_middleware(State(tenancy): State<Tenancy>, request: Request, next: Next) -> Response {
+    let (parts, body) = request.into_parts();
+    let tenant = tenancy.resolver.resolve(&parts);
+    let mut request = Request::from_parts(parts, body);
+
+    let provider = match tenant.as_deref() {
+        Some(tenant) => tenancy.providers.get(tenant).await,
+        None => None,
+    };
+
+    if let Some(provider) = provider {
+        scope::begin(&mut request, &provider);
+        next.run(request).await
+    } else {
+        (tenancy.providers.rejection)(tenant.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::IntoFuture;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{prelude::*, Inject, InjectAll, Stacking};
+    use axum::{routing::get, test_helpers::TestClient, Router};
+    use di::{injectable, Injectable, ServiceCollection};
+
+    trait Storage: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    #[injectable(Storage)]
+    struct BlobStorage;
+
+    #[injectable(Storage)]
+    struct DiskStorage;
+
+    impl Storage for BlobStorage {
+        fn name(&self) -> &str {
+            "Blob"
+        }
+    }
+
+    impl Storage for DiskStorage {
+        fn name(&self) -> &str {
+            "Disk"
+        }
+    }
+
+    async fn handler(Inject(storage): Inject<dyn Storage>) -> String {
+        storage.name().into()
+    }
+
+    fn new_providers() -> TenantProviders {
+        TenantProviders::new()
+            .insert(
+                "contoso",
+                ServiceCollection::new()
+                    .add(BlobStorage::scoped())
+                    .build_provider()
+                    .unwrap(),
+            )
+            .insert(
+                "fabrikam",
+                ServiceCollection::new()
+                    .add(DiskStorage::scoped())
+                    .build_provider()
+                    .unwrap(),
+            )
+    }
+
+    #[tokio::test]
+    async fn inject_tenant_service_resolved_by_header() {
+        // arrange
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_tenant_providers(TenantResolver::Header("x-tenant"), new_providers());
+
+        let client = TestClient::new(app);
+
+        // act
+        let contoso = client
+            .get("/test")
+            .header("x-tenant", "contoso")
+            .into_future()
+            .await
+            .text()
+            .await;
+        let fabrikam = client
+            .get("/test")
+            .header("x-tenant", "fabrikam")
+            .into_future()
+            .await
+            .text()
+            .await;
+
+        // assert
+        assert_eq!(&contoso, "Blob");
+        assert_eq!(&fabrikam, "Disk");
+    }
+
+    #[tokio::test]
+    async fn inject_tenant_service_resolved_by_host() {
+        // arrange
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_tenant_providers(TenantResolver::Host, new_providers());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").header("host", "fabrikam:8080").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Disk");
+    }
+
+    #[tokio::test]
+    async fn inject_tenant_service_resolved_by_path_prefix() {
+        // arrange
+        let app = Router::new()
+            .route("/{tenant}/test", get(handler))
+            .with_tenant_providers(TenantResolver::PathPrefix, new_providers());
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/contoso/test").into_future().await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Blob");
+    }
+
+    #[tokio::test]
+    async fn request_should_fail_for_missing_or_unknown_tenant() {
+        // arrange
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_tenant_providers(TenantResolver::Header("x-tenant"), new_providers());
+
+        let client = TestClient::new(app);
+
+        // act
+        let missing = client.get("/test").into_future().await;
+        let unknown = client.get("/test").header("x-tenant", "northwind").into_future().await;
+
+        // assert
+        assert_eq!(missing.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn request_should_use_custom_rejection_for_unknown_tenant() {
+        // arrange
+        let providers = new_providers().with_rejection(|_| StatusCode::FORBIDDEN.into_response());
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_tenant_providers(TenantResolver::Header("x-tenant"), providers);
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client.get("/test").header("x-tenant", "northwind").into_future().await;
+
+        // assert
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn tenant_scope_should_replace_parent_scopes() {
+        // arrange
+        async fn handler(InjectAll(storages): InjectAll<dyn Storage>) -> String {
+            let names: Vec<_> = storages.iter().map(|storage| storage.name()).collect();
+            names.join(",")
+        }
+
+        let tenant = Router::new()
+            .route("/test", get(handler))
+            .with_tenant_providers(TenantResolver::Header("x-tenant"), new_providers());
+        let child = Router::new()
+            .nest("/tenant", tenant)
+            .with_provider_stacking(ServiceProvider::default(), Stacking::Child);
+        let app = Router::new().nest("/child", child).with_provider(
+            ServiceCollection::new()
+                .add(BlobStorage::scoped())
+                .build_provider()
+                .unwrap(),
+        );
+
+        let client = TestClient::new(app);
+
+        // act
+        let response = client
+            .get("/child/tenant/test")
+            .header("x-tenant", "fabrikam")
+            .into_future()
+            .await;
+        let text = response.text().await;
+
+        // assert
+        assert_eq!(&text, "Disk");
+    }
+
+    #[tokio::test]
+    async fn tenant_provider_should_be_created_once_by_factory() {
+        // arrange
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+        fn tenant(parts: &Parts) -> Option<String> {
+            parts.uri.query().map(ToOwned::to_owned)
+        }
+
+        let providers = TenantProviders::new().with_factory(|tenant| {
+            if tenant == "northwind" {
+                CREATED.fetch_add(1, Ordering::SeqCst);
+                ServiceCollection::new()
+                    .add(DiskStorage::singleton())
+                    .build_provider()
+                    .ok()
+            } else {
+                None
+            }
+        });
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_tenant_providers(TenantResolver::Custom(tenant), providers);
+
+        let client = TestClient::new(app);
+
+        // act
+        let first = client.get("/test?northwind").into_future().await.text().await;
+        let second = client.get("/test?northwind").into_future().await.text().await;
+        let unknown = client.get("/test?contoso").into_future().await;
+
+        // assert
+        assert_eq!(&first, "Disk");
+        assert_eq!(&second, "Disk");
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+        assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_tenant_should_be_cached_by_factory() {
+        // arrange
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+        let providers = TenantProviders::new().with_factory(|_| {
+            CREATED.fetch_add(1, Ordering::SeqCst);
+            None
+        });
+        let app = Router::new()
+            .route("/test", get(handler))
+            .with_tenant_providers(TenantResolver::Header("x-tenant"), providers);
+
+        let client = TestClient::new(app);
+
+        // act
+        let first = client.get("/test").header("x-tenant", "northwind").into_future().await;
+        let second = client.get("/test").header("x-tenant", "northwind").into_future().await;
+
+        // assert
+        assert_eq!(first.status(), StatusCode::NOT_FOUND);
+        assert_eq!(second.status(), StatusCode::NOT_FOUND);
+        assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn oldest_tenant_should_be_evicted_when_capacity_is_reached() {
+        // arrange
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+        let providers = TenantProviders::new().with_capacity(1).with_factory(|_| {
+            CREATED.fetch_add(1, Ordering::SeqCst);
+            Some(ServiceProvider::default())
+        });
+
+        // act
+        let _ = providers.get("contoso").await;
+        let _ = providers.get("fabrikam").await;
+        let _ = providers.get("fabrikam").await;
+        let _ = providers.get("contoso").await;
+
+        // assert
+        assert_eq!(CREATED.load(Ordering::SeqCst), 3);
+    }
+}
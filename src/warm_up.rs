@@ -0,0 +1,330 @@
+use crate::scoped_value::{self, is_scoped_value};
+use di::{ServiceCollection, ServiceDescriptor, ServiceLifetime, ServiceProvider, Type};
+use std::any::Any;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Represents the services that are resolved when warming up a service provider.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WarmUp {
+    /// Indicates only singleton services are resolved.
+    #[default]
+    Singletons,
+
+    /// Indicates all services are resolved.
+    ///
+    /// # Remarks
+    ///
+    /// Scoped and transient services, which includes every service that can be injected by a route, are resolved
+    /// from a scope that is dropped after the warm up completes. Services resolved from a
+    /// [scoped value](crate::ScopedValue), such as the [request context](crate::RequestContext), and the services
+    /// that resolve them, directly or transitively, are skipped because the value is only set for a request.
+    All,
+}
+
+/// Represents the error that occurs when one or more services cannot be resolved while warming up a service
+/// provider.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WarmUpError(Vec<(String, String)>);
+
+impl WarmUpError {
+    /// Gets the name of each service that cannot be resolved and the reason it cannot be resolved.
+    pub fn failures(&self) -> &[(String, String)] {
+        &self.0
+    }
+}
+
+impl Display for WarmUpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "{} service(s) cannot be resolved:", self.0.len())?;
+
+        for (name, reason) in &self.0 {
+            write!(f, "\n  '{}': {}", name, reason)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for WarmUpError {}
+
+/// Gets the types of the services that match a predicate and of all services that depend on them, directly or
+/// transitively.
+fn dependents(services: &ServiceCollection, matches: impl Fn(&ServiceDescriptor) -> bool) -> HashSet<&Type> {
+    let mut types: HashSet<_> = services
+        .iter()
+        .filter(|d| matches(d))
+        .map(|d| d.service_type())
+        .collect();
+    let mut changed = !types.is_empty();
+
+    while changed {
+        changed = false;
+
+        for descriptor in services {
+            if !types.contains(descriptor.service_type())
+                && descriptor
+                    .dependencies()
+                    .iter()
+                    .any(|d| types.contains(d.injected_type()))
+            {
+                types.insert(descriptor.service_type());
+                changed = true;
+            }
+        }
+    }
+
+    types
+}
+
+/// Gets the message of a panic, if any.
+fn message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "The service panicked while it was resolved.".into()
+    }
+}
+
+/// Resolves the specified services from a service provider.
+///
+/// # Arguments
+///
+/// * `services` - the [service collection](ServiceCollection) the service provider was built from
+/// * `provider` - the [service provider](ServiceProvider) to warm up
+/// * `warm_up` - the [services](WarmUp) to resolve
+///
+/// # Remarks
+///
+/// The dependencies of the services have already been validated when the service provider was built, so each service
+/// is resolved and a service that panics, such as a factory that cannot read its configuration, is reported as a
+/// failure. Every service is resolved so that all of the failures are reported together. A scoped or transient service
+/// that resolves a [scoped value](crate::ScopedValue) is skipped, even when the dependency is not declared, such as a
+/// factory that resolves the value itself. A singleton that resolves a scoped value can never be resolved and is
+/// reported as a failure.
+pub(crate) fn warm_up(
+    services: &ServiceCollection,
+    provider: &ServiceProvider,
+    warm_up: WarmUp,
+) -> Result<(), WarmUpError> {
+    let request_only = dependents(services, is_scoped_value);
+    let scope = provider.create_scope();
+    let mut failures = Vec::new();
+
+    for descriptor in services {
+        let singleton = descriptor.lifetime() == ServiceLifetime::Singleton;
+
+        // services that depend on a value that is only set for a request cannot be resolved outside of a request
+        if !singleton && (warm_up != WarmUp::All || request_only.contains(descriptor.service_type())) {
+            continue;
+        }
+
+        // singleton instances are shared with the service provider; other instances are only created to verify them
+        let provider = if singleton { provider } else { &scope };
+        let reason = match catch_unwind(AssertUnwindSafe(|| scoped_value::resolve(|| descriptor.get(provider)))) {
+            Ok(Ok(_)) => continue,
+            Ok(Err(_)) if !singleton => continue,
+            Ok(Err(name)) => format!("The scoped value '{}' is only available during a request.", name),
+            Err(payload) => message(payload.as_ref()),
+        };
+
+        failures.push((descriptor.service_type().name().to_owned(), reason));
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(WarmUpError(failures))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, RequestContext};
+    use di::{injectable, scoped_factory, singleton_factory, transient_factory, Injectable, Ref};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Settings;
+    struct Session;
+    struct Cache;
+    struct Store;
+
+    #[injectable]
+    struct Tracer {
+        _context: Ref<RequestContext>,
+    }
+
+    #[injectable]
+    struct Auditor {
+        _tracer: Ref<Tracer>,
+    }
+
+    #[test]
+    fn warm_up_should_resolve_singletons() {
+        // arrange
+        let created = Ref::new(AtomicUsize::new(0));
+        let counter = created.clone();
+        let mut services = ServiceCollection::new();
+
+        services.add(singleton_factory(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ref::new(Settings)
+        }));
+
+        let provider = services.build_provider().unwrap();
+
+        // act
+        services.warm_up(&provider, WarmUp::Singletons).unwrap();
+        let _ = provider.get_required::<Settings>();
+
+        // assert
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn warm_up_should_report_all_failures() {
+        // arrange
+        let mut services = ServiceCollection::new();
+
+        services
+            .add(singleton_factory(|_| -> Ref<Cache> {
+                panic!("The cache is not configured.")
+            }))
+            .add(singleton_factory(|_| Ref::new(Settings)))
+            .add(singleton_factory(|_| -> Ref<Store> {
+                panic!("{} is not configured.", "The store")
+            }));
+
+        let provider = services.build_provider().unwrap();
+
+        // act
+        let error = services.warm_up(&provider, WarmUp::Singletons).unwrap_err();
+
+        // assert
+        assert_eq!(
+            error.failures(),
+            &[
+                (
+                    "di_axum::warm_up::tests::Cache".to_owned(),
+                    "The cache is not configured.".to_owned()
+                ),
+                (
+                    "di_axum::warm_up::tests::Store".to_owned(),
+                    "The store is not configured.".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn warm_up_should_skip_scoped_values() {
+        // arrange
+        let mut services = ServiceCollection::new();
+
+        services.add_request_context().add_scoped_value::<Session>();
+
+        let provider = services.build_provider().unwrap();
+
+        // act
+        let result = services.warm_up(&provider, WarmUp::All);
+
+        // assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn warm_up_should_skip_services_that_depend_on_scoped_values() {
+        // arrange
+        let mut services = ServiceCollection::new();
+
+        services
+            .add(Tracer::scoped())
+            .add(Auditor::transient())
+            .add_request_context();
+
+        let provider = services.build_provider().unwrap();
+
+        // act
+        let result = services.warm_up(&provider, WarmUp::All);
+
+        // assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn warm_up_should_skip_factories_that_resolve_scoped_values() {
+        // arrange
+        let mut services = ServiceCollection::new();
+
+        services
+            .add(transient_factory(|sp| {
+                let _ = sp.get_required::<RequestContext>();
+                Ref::new(Session)
+            }))
+            .add_request_context();
+
+        let provider = services.build_provider().unwrap();
+
+        // act
+        let result = services.warm_up(&provider, WarmUp::All);
+
+        // assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn warm_up_should_report_singletons_that_resolve_scoped_values() {
+        // arrange
+        let mut services = ServiceCollection::new();
+
+        services
+            .add(singleton_factory(|sp| {
+                let _ = sp.get_required::<RequestContext>();
+                Ref::new(Session)
+            }))
+            .add_request_context();
+
+        let provider = services.build_provider().unwrap();
+
+        // act
+        let error = services.warm_up(&provider, WarmUp::Singletons).unwrap_err();
+
+        // assert
+        assert_eq!(
+            error.failures(),
+            &[(
+                "di_axum::warm_up::tests::Session".to_owned(),
+                "The scoped value 'di_axum::context::RequestContext' is only available during a request.".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn warm_up_should_only_resolve_scoped_services_for_all() {
+        // arrange
+        let created = Ref::new(AtomicUsize::new(0));
+        let counter = created.clone();
+        let mut services = ServiceCollection::new();
+
+        services.add(scoped_factory(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ref::new(Session)
+        }));
+
+        let provider = services.build_provider().unwrap();
+
+        // act
+        services.warm_up(&provider, WarmUp::Singletons).unwrap();
+        let singletons = created.load(Ordering::SeqCst);
+        services.warm_up(&provider, WarmUp::All).unwrap();
+
+        // assert
+        assert_eq!(singletons, 0);
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+}